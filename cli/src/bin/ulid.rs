@@ -1,9 +1,8 @@
 extern crate structopt;
 
 use std::io::{self, Write};
-use ulid::{Generator, Ulid};
+use ulid::{Generator, OverflowPolicy, Ulid};
 
-use std::{thread, time::Duration};
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -30,28 +29,11 @@ fn main() {
 
 fn generate(count: u32, monotonic: bool) {
     let stdout = io::stdout();
-    let stderr = io::stderr();
     let mut locked = stdout.lock();
-    let mut err_locked = stderr.lock();
     if monotonic {
-        let mut generator = Generator::new();
-        let mut i = 0;
-        while i < count {
-            match generator.generate() {
-                Ok(ulid) => {
-                    writeln!(&mut locked, "{}", ulid).unwrap();
-                    i += 1;
-                }
-                Err(_) => {
-                    writeln!(
-                        &mut err_locked,
-                        "Failed to create new ulid due to overflow, sleeping 1 ms"
-                    )
-                    .unwrap();
-                    thread::sleep(Duration::from_millis(1));
-                    // do not increment i
-                }
-            }
+        let mut generator = Generator::with_policy(OverflowPolicy::Sleep);
+        for _ in 0..count {
+            writeln!(&mut locked, "{}", generator.generate_blocking()).unwrap();
         }
     } else {
         for _ in 0..count {
@@ -0,0 +1,66 @@
+//! The parts of the SQLite C API used by the extension.
+//!
+//! A loadable extension must call SQLite through the `sqlite3_api_routines` table passed to its
+//! entry point, not through linked symbols, so that it uses the same library as the host. SQLite
+//! only ever appends to this table, so the prefix declared here is stable. The indices of the
+//! skipped entries follow `sqlite3ext.h`.
+
+use std::os::raw::{c_char, c_int, c_uchar, c_void};
+
+pub const SQLITE_OK: c_int = 0;
+
+pub const SQLITE_INTEGER: c_int = 1;
+pub const SQLITE_FLOAT: c_int = 2;
+pub const SQLITE_TEXT: c_int = 3;
+pub const SQLITE_BLOB: c_int = 4;
+
+pub const SQLITE_UTF8: c_int = 1;
+pub const SQLITE_DETERMINISTIC: c_int = 0x800;
+pub const SQLITE_INNOCUOUS: c_int = 0x200000;
+
+/// The destructor which makes SQLite copy a result before the call returns
+pub const SQLITE_TRANSIENT: isize = -1;
+
+pub enum Sqlite3 {}
+pub enum Context {}
+pub enum Value {}
+
+pub type ScalarFunction = unsafe extern "C" fn(*mut Context, c_int, *mut *mut Value);
+
+type Unused = *const c_void;
+
+/// A prefix of `sqlite3_api_routines`
+#[repr(C)]
+pub struct ApiRoutines {
+    _unused0: [Unused; 45],
+    pub create_function: unsafe extern "C" fn(
+        db: *mut Sqlite3,
+        name: *const c_char,
+        arguments: c_int,
+        flags: c_int,
+        user_data: *mut c_void,
+        function: Option<ScalarFunction>,
+        step: Option<ScalarFunction>,
+        finalize: Option<unsafe extern "C" fn(*mut Context)>,
+    ) -> c_int,
+    _unused46: [Unused; 32],
+    pub result_blob: unsafe extern "C" fn(*mut Context, *const c_void, c_int, isize),
+    _unused79: [Unused; 1],
+    pub result_error: unsafe extern "C" fn(*mut Context, *const c_char, c_int),
+    _unused81: [Unused; 2],
+    pub result_int64: unsafe extern "C" fn(*mut Context, i64),
+    pub result_null: unsafe extern "C" fn(*mut Context),
+    pub result_text: unsafe extern "C" fn(*mut Context, *const c_char, c_int, isize),
+    _unused86: [Unused; 15],
+    pub user_data: unsafe extern "C" fn(*mut Context) -> *mut c_void,
+    pub value_blob: unsafe extern "C" fn(*mut Value) -> *const c_void,
+    pub value_bytes: unsafe extern "C" fn(*mut Value) -> c_int,
+    _unused104: [Unused; 3],
+    pub value_int64: unsafe extern "C" fn(*mut Value) -> i64,
+    _unused108: [Unused; 1],
+    pub value_text: unsafe extern "C" fn(*mut Value) -> *const c_uchar,
+    _unused110: [Unused; 3],
+    pub value_type: unsafe extern "C" fn(*mut Value) -> c_int,
+}
+
+const _: () = assert!(std::mem::size_of::<ApiRoutines>() == 114 * std::mem::size_of::<usize>());
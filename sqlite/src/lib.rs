@@ -0,0 +1,266 @@
+//! An SQLite loadable extension for generating and inspecting ULIDs.
+//!
+//! Build it with `cargo build --release -p ulid-sqlite` and load the library, e.g. with
+//! `.load ./libulid_sqlite` in the `sqlite3` shell. It provides these scalar functions:
+//!
+//! * `ulid()`: a new Ulid as its 26-character string
+//! * `ulid_monotonic()`: a new Ulid, larger than the previous one generated by this process
+//! * `ulid_bytes()`: a new Ulid as a 16-byte blob, which sorts in the same order as the Ulids
+//! * `ulid_timestamp(x)`: the timestamp of a Ulid in milliseconds since the unix epoch
+//! * `ulid_to_uuid(x)`: a Ulid as a hyphenated UUID string
+//! * `ulid_from_uuid(x)`: a UUID, as a string or 16-byte blob, as a Ulid string
+//! * `ulid_min_for_time(t)`: the smallest Ulid with the timestamp `t`, for range queries
+//!
+//! Functions taking a Ulid accept both its string and 16-byte blob form, and return `NULL` for a
+//! `NULL` argument.
+
+mod ffi;
+
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use ulid::Ulid;
+
+use crate::ffi::*;
+
+static API: AtomicPtr<ApiRoutines> = AtomicPtr::new(ptr::null_mut());
+
+/// An argument of a function call
+enum Arg<'a> {
+    Null,
+    Integer(i64),
+    Float,
+    Text(&'a [u8]),
+    Blob(&'a [u8]),
+}
+
+/// The result of a function call
+enum Output {
+    Null,
+    Integer(i64),
+    Text(String),
+    Blob([u8; 16]),
+}
+
+struct Function {
+    /// The NUL-terminated name
+    name: &'static str,
+    arguments: c_int,
+    deterministic: bool,
+    call: fn(&[Arg]) -> Result<Output, String>,
+}
+
+static FUNCTIONS: [Function; 7] = [
+    Function {
+        name: "ulid\0",
+        arguments: 0,
+        deterministic: false,
+        call: |_| Ok(Output::Text(Ulid::generate().to_string())),
+    },
+    Function {
+        name: "ulid_monotonic\0",
+        arguments: 0,
+        deterministic: false,
+        call: |_| Ok(Output::Text(Ulid::generate_monotonic().to_string())),
+    },
+    Function {
+        name: "ulid_bytes\0",
+        arguments: 0,
+        deterministic: false,
+        call: |_| Ok(Output::Blob(Ulid::generate().to_bytes())),
+    },
+    Function {
+        name: "ulid_timestamp\0",
+        arguments: 1,
+        deterministic: true,
+        call: |args| {
+            Ok(ulid_arg(&args[0])?.map_or(Output::Null, |ulid| {
+                Output::Integer(ulid.timestamp_ms() as i64)
+            }))
+        },
+    },
+    Function {
+        name: "ulid_to_uuid\0",
+        arguments: 1,
+        deterministic: true,
+        call: |args| {
+            Ok(ulid_arg(&args[0])?.map_or(Output::Null, |ulid| Output::Text(format_uuid(ulid.0))))
+        },
+    },
+    Function {
+        name: "ulid_from_uuid\0",
+        arguments: 1,
+        deterministic: true,
+        call: |args| {
+            let uuid = match args[0] {
+                Arg::Null => return Ok(Output::Null),
+                Arg::Blob(bytes) if bytes.len() == 16 => {
+                    let mut array = [0; 16];
+                    array.copy_from_slice(bytes);
+                    u128::from_be_bytes(array)
+                }
+                Arg::Text(text) => parse_uuid(text).ok_or("invalid uuid")?,
+                _ => return Err("expected a uuid string or 16 bytes".into()),
+            };
+            Ok(Output::Text(Ulid(uuid).to_string()))
+        },
+    },
+    Function {
+        name: "ulid_min_for_time\0",
+        arguments: 1,
+        deterministic: true,
+        call: |args| match args[0] {
+            Arg::Null => Ok(Output::Null),
+            Arg::Integer(timestamp) if (0..1 << 48).contains(&timestamp) => Ok(Output::Text(
+                Ulid::from_parts(timestamp as u64, 0).to_string(),
+            )),
+            _ => Err("expected milliseconds since the unix epoch, below 2^48".into()),
+        },
+    },
+];
+
+/// Decode a Ulid from its string or 16 bytes
+fn ulid_arg(arg: &Arg) -> Result<Option<Ulid>, String> {
+    match *arg {
+        Arg::Null => Ok(None),
+        Arg::Blob(bytes) if bytes.len() == 16 => {
+            let mut array = [0; 16];
+            array.copy_from_slice(bytes);
+            Ok(Some(Ulid::from_bytes(array)))
+        }
+        Arg::Text(text) => {
+            let text = std::str::from_utf8(text).map_err(|_| "invalid ulid: invalid utf-8")?;
+            Ulid::from_string(text)
+                .map(Some)
+                .map_err(|err| format!("invalid ulid: {}", err))
+        }
+        _ => Err("expected a ulid string or 16 bytes".into()),
+    }
+}
+
+/// Parse a hyphenated or simple UUID string
+fn parse_uuid(text: &[u8]) -> Option<u128> {
+    let digits: Vec<u8> = match text.len() {
+        32 => text.to_vec(),
+        36 if [8, 13, 18, 23].iter().all(|&index| text[index] == b'-') => text
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| ![8, 13, 18, 23].contains(&index))
+            .map(|(_, &digit)| digit)
+            .collect(),
+        _ => return None,
+    };
+    if !digits.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+    u128::from_str_radix(std::str::from_utf8(&digits).ok()?, 16).ok()
+}
+
+fn format_uuid(value: u128) -> String {
+    let hex = format!("{:032x}", value);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+impl Arg<'_> {
+    unsafe fn read(api: &ApiRoutines, value: *mut Value) -> Self {
+        unsafe fn slice<'a>(pointer: *const u8, len: c_int) -> &'a [u8] {
+            if pointer.is_null() || len <= 0 {
+                &[]
+            } else {
+                std::slice::from_raw_parts(pointer, len as usize)
+            }
+        }
+
+        match (api.value_type)(value) {
+            SQLITE_INTEGER => Arg::Integer((api.value_int64)(value)),
+            SQLITE_FLOAT => Arg::Float,
+            SQLITE_TEXT => {
+                let text = (api.value_text)(value);
+                Arg::Text(slice(text, (api.value_bytes)(value)))
+            }
+            SQLITE_BLOB => {
+                let blob = (api.value_blob)(value);
+                Arg::Blob(slice(blob.cast(), (api.value_bytes)(value)))
+            }
+            _ => Arg::Null,
+        }
+    }
+}
+
+unsafe extern "C" fn call(context: *mut Context, argc: c_int, argv: *mut *mut Value) {
+    let api = &*API.load(Ordering::Acquire);
+    let function = &*((api.user_data)(context) as *const Function);
+    let args: Vec<Arg> = if argc > 0 {
+        std::slice::from_raw_parts(argv, argc as usize)
+            .iter()
+            .map(|&value| Arg::read(api, value))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    match (function.call)(&args) {
+        Ok(Output::Null) => (api.result_null)(context),
+        Ok(Output::Integer(value)) => (api.result_int64)(context, value),
+        Ok(Output::Text(text)) => (api.result_text)(
+            context,
+            text.as_ptr().cast(),
+            text.len() as c_int,
+            SQLITE_TRANSIENT,
+        ),
+        Ok(Output::Blob(bytes)) => (api.result_blob)(
+            context,
+            bytes.as_ptr().cast(),
+            bytes.len() as c_int,
+            SQLITE_TRANSIENT,
+        ),
+        Err(message) => {
+            let name = function.name.trim_end_matches('\0');
+            let message = format!("{}: {}", name, message);
+            (api.result_error)(context, message.as_ptr().cast(), message.len() as c_int)
+        }
+    }
+}
+
+/// The entry point, which SQLite derives from the name of the library file
+///
+/// # Safety
+///
+/// Must only be called by SQLite when loading the extension.
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_ulidsqlite_init(
+    db: *mut Sqlite3,
+    _error: *mut *mut c_char,
+    api: *const ApiRoutines,
+) -> c_int {
+    API.store(api as *mut ApiRoutines, Ordering::Release);
+
+    for function in FUNCTIONS.iter() {
+        let mut flags = SQLITE_UTF8 | SQLITE_INNOCUOUS;
+        if function.deterministic {
+            flags |= SQLITE_DETERMINISTIC;
+        }
+        let result = ((*api).create_function)(
+            db,
+            function.name.as_ptr().cast(),
+            function.arguments,
+            flags,
+            function as *const Function as *mut c_void,
+            Some(call),
+            None,
+            None,
+        );
+        if result != SQLITE_OK {
+            return result;
+        }
+    }
+    SQLITE_OK
+}
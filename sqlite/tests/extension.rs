@@ -0,0 +1,148 @@
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::path::PathBuf;
+
+use rusqlite::types::Value;
+use rusqlite::{params, Connection};
+use ulid::Ulid;
+
+/// The extension built next to this test
+fn library() -> PathBuf {
+    let mut path = std::env::current_exe().unwrap();
+    path.pop();
+    path.push(format!("{}ulid_sqlite{}", DLL_PREFIX, DLL_SUFFIX));
+    path
+}
+
+fn connection() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    unsafe {
+        conn.load_extension_enable().unwrap();
+        conn.load_extension(library(), None).unwrap();
+        conn.load_extension_disable().unwrap();
+    }
+    conn
+}
+
+fn query(conn: &Connection, sql: &str) -> rusqlite::Result<Value> {
+    conn.query_row(sql, [], |row| row.get(0))
+}
+
+#[test]
+fn generate() {
+    let conn = connection();
+
+    let Value::Text(text) = query(&conn, "SELECT ulid()").unwrap() else {
+        panic!("ulid() is not text");
+    };
+    assert!(Ulid::from_string(&text).is_ok());
+
+    let Value::Blob(bytes) = query(&conn, "SELECT ulid_bytes()").unwrap() else {
+        panic!("ulid_bytes() is not a blob");
+    };
+    assert_eq!(bytes.len(), 16);
+
+    let ulids: Vec<String> = conn
+        .prepare(
+            "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 100) \
+             SELECT ulid_monotonic() FROM n",
+        )
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(ulids.len(), 100);
+    assert!(ulids.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn default_and_range_query() {
+    let conn = connection();
+    conn.execute_batch(
+        "CREATE TABLE events (id TEXT PRIMARY KEY DEFAULT (ulid_monotonic()), name TEXT);
+         INSERT INTO events (name) VALUES ('first'), ('second');",
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO events VALUES (?1, 'old')",
+        params![Ulid::from_parts(1_000, 0).to_string()],
+    )
+    .unwrap();
+
+    let names: Vec<String> = conn
+        .prepare("SELECT name FROM events WHERE id >= ulid_min_for_time(?1) ORDER BY id")
+        .unwrap()
+        .query_map([1_000_000_i64], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(names, ["first", "second"]);
+}
+
+#[test]
+fn inspect() {
+    let conn = connection();
+    let text = "01D39ZY06FGSCTVN4T2V9PKHFZ";
+    let uuid = "0168d3ff-00cf-8659-add4-9a16d369c5ff";
+    let ulid = Ulid::from_string(text).unwrap();
+
+    let timestamp = Value::Integer(ulid.timestamp_ms() as i64);
+    let sql = format!("SELECT ulid_timestamp('{}')", text);
+    assert_eq!(query(&conn, &sql).unwrap(), timestamp);
+    let timestamp_of_bytes: Value = conn
+        .query_row("SELECT ulid_timestamp(?1)", [ulid.to_bytes()], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert_eq!(timestamp_of_bytes, timestamp);
+
+    let sql = format!("SELECT ulid_to_uuid('{}')", text);
+    assert_eq!(query(&conn, &sql).unwrap(), Value::Text(uuid.into()));
+
+    for form in &[uuid.to_string(), uuid.to_uppercase(), uuid.replace('-', "")] {
+        let from_uuid: Value = conn
+            .query_row("SELECT ulid_from_uuid(?1)", [form], |row| row.get(0))
+            .unwrap();
+        assert_eq!(from_uuid, Value::Text(text.into()));
+    }
+
+    let sql = "SELECT ulid_min_for_time(1000)";
+    assert_eq!(
+        query(&conn, sql).unwrap(),
+        Value::Text(Ulid::from_parts(1000, 0).to_string())
+    );
+
+    for function in &[
+        "ulid_timestamp",
+        "ulid_to_uuid",
+        "ulid_from_uuid",
+        "ulid_min_for_time",
+    ] {
+        let sql = format!("SELECT {}(NULL)", function);
+        assert_eq!(query(&conn, &sql).unwrap(), Value::Null);
+    }
+}
+
+#[test]
+fn invalid_arguments() {
+    let conn = connection();
+    let error = |sql: &str| query(&conn, sql).unwrap_err().to_string();
+
+    assert_eq!(
+        error("SELECT ulid_timestamp('not a ulid')"),
+        "ulid_timestamp: invalid ulid: invalid length"
+    );
+    assert_eq!(
+        error("SELECT ulid_to_uuid(x'0102')"),
+        "ulid_to_uuid: expected a ulid string or 16 bytes"
+    );
+    assert_eq!(
+        error("SELECT ulid_from_uuid('0168d3ff-00cf-8659-add4')"),
+        "ulid_from_uuid: invalid uuid"
+    );
+    assert_eq!(
+        error("SELECT ulid_min_for_time(-1)"),
+        "ulid_min_for_time: expected milliseconds since the unix epoch, below 2^48"
+    );
+    assert!(query(&conn, "SELECT ulid(1)").is_err());
+}
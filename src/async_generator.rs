@@ -0,0 +1,313 @@
+//! Monotonic generation for async code.
+//!
+//! When the random bits of a millisecond are exhausted, [`AsyncGenerator`] waits for the clock to
+//! move on by awaiting a [`Timer`] instead of blocking the thread, so an executor worker is never
+//! put to sleep. The generator is not tied to any runtime.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+
+use futures_core::Stream;
+
+use crate::{ClockRegressionPolicy, Generator, OverflowPolicy, Ulid};
+
+/// A source of sleep futures used by [`AsyncGenerator`] to wait out an overflowed millisecond
+///
+/// Any `Fn(Duration) -> impl Future<Output = ()>` is a `Timer`, so a runtime's sleep function can
+/// be plugged in directly.
+///
+/// # Example
+/// ```rust,ignore
+/// let generator = ulid::AsyncGenerator::with_timer(tokio::time::sleep);
+/// ```
+pub trait Timer {
+    /// The future returned by [`Timer::sleep`]
+    type Sleep: Future<Output = ()>;
+
+    /// Create a future which completes after roughly the given duration
+    fn sleep(&self, duration: Duration) -> Self::Sleep;
+}
+
+impl<F, Fut> Timer for F
+where
+    F: Fn(Duration) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    type Sleep = Fut;
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        self(duration)
+    }
+}
+
+/// A runtime-agnostic [`Timer`] which yields to the executor instead of sleeping
+///
+/// The task is rescheduled immediately, so the clock is polled every time the executor gets back
+/// to it. Plug in a runtime timer with [`AsyncGenerator::with_timer`] to avoid the busy polling.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct YieldTimer;
+
+impl Timer for YieldTimer {
+    type Sleep = YieldNow;
+
+    fn sleep(&self, _duration: Duration) -> Self::Sleep {
+        YieldNow { yielded: false }
+    }
+}
+
+/// Future returned by [`YieldTimer`]
+#[derive(Debug)]
+pub struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            Poll::Ready(())
+        } else {
+            self.yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// A shareable monotonic Ulid generator for async code
+///
+/// All methods take `&self`, so the generator can be shared between tasks behind an `Arc` or in a
+/// `static`. On overflow the [`Timer`] is awaited until the clock moves past the overflowed
+/// millisecond, unless the generator's [`OverflowPolicy`] commits the overflow instead. Likewise,
+/// a clock regression is waited out unless the [`ClockRegressionPolicy`] is `Continue`.
+///
+/// # Example
+/// ```rust
+/// use ulid::AsyncGenerator;
+///
+/// let generator = AsyncGenerator::new();
+///
+/// let (ulid1, ulid2) = futures::executor::block_on(async {
+///     (generator.generate_async().await, generator.generate_async().await)
+/// });
+///
+/// assert!(ulid1 < ulid2);
+/// ```
+#[derive(Debug)]
+pub struct AsyncGenerator<T = YieldTimer> {
+    generator: Mutex<Generator>,
+    timer: T,
+}
+
+impl AsyncGenerator {
+    /// Create a new async generator which yields to the executor while waiting
+    pub const fn new() -> AsyncGenerator {
+        AsyncGenerator::with_timer(YieldTimer)
+    }
+}
+
+impl Default for AsyncGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Timer> AsyncGenerator<T> {
+    /// Create a new async generator which waits with the given timer
+    pub const fn with_timer(timer: T) -> AsyncGenerator<T> {
+        AsyncGenerator::from_generator(Generator::new(), timer)
+    }
+
+    /// Create a new async generator from an existing generator and timer
+    ///
+    /// The generator's [`OverflowPolicy`] is kept. [`OverflowPolicy::BorrowFuture`] and
+    /// [`OverflowPolicy::Random`] commit the overflow, every other policy awaits the timer.
+    pub const fn from_generator(generator: Generator, timer: T) -> AsyncGenerator<T> {
+        AsyncGenerator {
+            generator: Mutex::new(generator),
+            timer,
+        }
+    }
+
+    /// Generate a new Ulid, waiting for the next millisecond without blocking if the random bits
+    /// would overflow. Each call is guaranteed to provide a Ulid with a larger value than the last
+    /// call on this generator.
+    pub async fn generate_async(&self) -> Ulid {
+        loop {
+            match self.try_generate() {
+                Ok(ulid) => return ulid,
+                Err(wait) => self.timer.sleep(wait).await,
+            }
+        }
+    }
+
+    /// Create an endless stream of monotonic Ulids from this generator
+    ///
+    /// # Example
+    /// ```rust
+    /// use futures::StreamExt;
+    /// use ulid::AsyncGenerator;
+    ///
+    /// let generator = AsyncGenerator::new();
+    /// let ulids: Vec<_> = futures::executor::block_on(generator.stream().take(3).collect());
+    ///
+    /// assert!(ulids[0] < ulids[1] && ulids[1] < ulids[2]);
+    /// ```
+    pub fn stream(&self) -> UlidStream<'_, T> {
+        UlidStream {
+            generator: self,
+            sleep: None,
+        }
+    }
+
+    /// Generate a Ulid, or return how long to wait before trying again
+    fn try_generate(&self) -> Result<Ulid, Duration> {
+        let mut generator = self
+            .generator
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let now = generator.now();
+        if generator.clock_regression_policy() != ClockRegressionPolicy::Continue {
+            if let Some(regression) = generator.detect_clock_regression(now) {
+                return Err(regression.duration());
+            }
+        }
+
+        let policy = generator.policy();
+        match generator.generate_from_datetime(now) {
+            Ok(ulid) => Ok(ulid),
+            Err(overflow) => match policy {
+                OverflowPolicy::BorrowFuture => Ok(overflow.commit_overflow_increment()),
+                OverflowPolicy::Random => Ok(overflow.commit_overflow_random()),
+                _ => Err(wait_time(overflow.previous(), now)),
+            },
+        }
+    }
+}
+
+/// Time left until the clock moves past the millisecond of `previous`
+fn wait_time(previous: Ulid, now: SystemTime) -> Duration {
+    let next = SystemTime::UNIX_EPOCH + Duration::from_millis(previous.timestamp_ms() + 1);
+    next.duration_since(now)
+        .unwrap_or(Duration::ZERO)
+        .max(Duration::from_millis(1))
+}
+
+/// An endless [`Stream`] of monotonic Ulids created by [`AsyncGenerator::stream`]
+pub struct UlidStream<'a, T: Timer> {
+    generator: &'a AsyncGenerator<T>,
+    sleep: Option<Pin<Box<T::Sleep>>>,
+}
+
+impl<T: Timer> Stream for UlidStream<'_, T> {
+    type Item = Ulid;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Ulid>> {
+        loop {
+            if let Some(sleep) = self.sleep.as_mut() {
+                if sleep.as_mut().poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+                self.sleep = None;
+            }
+            match self.generator.try_generate() {
+                Ok(ulid) => return Poll::Ready(Some(ulid)),
+                Err(wait) => {
+                    let sleep = self.generator.timer.sleep(wait);
+                    self.sleep = Some(Box::pin(sleep));
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::StreamExt;
+    use std::cell::Cell;
+
+    fn overflowed_generator() -> Generator {
+        let mut generator = Generator::new();
+        let ulid = Ulid::from_datetime(crate::time_utils::now() + Duration::from_millis(5));
+        let maxed = Ulid::from_parts(ulid.timestamp_ms(), crate::bitmask!(Ulid::RAND_BITS));
+        let mut source = crate::StepRng::new(u64::MAX, 0);
+        let previous = generator
+            .generate_from_datetime_with_source(maxed.datetime(), &mut source)
+            .unwrap();
+        assert_eq!(previous, maxed);
+        generator
+    }
+
+    #[test]
+    fn test_generate_async_order() {
+        let generator = AsyncGenerator::default();
+        let ulid1 = block_on(generator.generate_async());
+        let ulid2 = block_on(generator.generate_async());
+        assert!(ulid1 < ulid2);
+    }
+
+    #[test]
+    fn test_generate_async_waits_with_timer() {
+        let sleeps = Cell::new(0);
+        let timer = |duration: Duration| {
+            sleeps.set(sleeps.get() + 1);
+            assert!(duration >= Duration::from_millis(1));
+            async move { std::thread::sleep(duration) }
+        };
+        let generator = AsyncGenerator::from_generator(overflowed_generator(), timer);
+
+        let ulid = block_on(generator.generate_async());
+
+        assert!(sleeps.get() > 0);
+        assert_ne!(ulid.random(), 0);
+    }
+
+    #[test]
+    fn test_generate_async_commits_with_policy() {
+        let mut generator = overflowed_generator();
+        generator.set_policy(OverflowPolicy::BorrowFuture);
+        let timer = |_: Duration| -> futures::future::Ready<()> { panic!("should not wait") };
+        let generator = AsyncGenerator::from_generator(generator, timer);
+
+        let ulid = block_on(generator.generate_async());
+        assert_eq!(ulid.random(), 0);
+    }
+
+    #[test]
+    fn test_stream_order() {
+        let generator = AsyncGenerator::from_generator(overflowed_generator(), YieldTimer);
+        let ulids: Vec<Ulid> = block_on(generator.stream().take(100).collect());
+
+        assert_eq!(ulids.len(), 100);
+        assert!(ulids.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_generate_async_waits_out_regression() {
+        let mut generator = Generator::new();
+        generator.set_clock_regression(Duration::ZERO, ClockRegressionPolicy::Error);
+        let ahead = crate::time_utils::now() + Duration::from_millis(20);
+        let previous = generator.generate_from_datetime(ahead).unwrap();
+
+        let waited = Cell::new(Duration::ZERO);
+        let timer = |duration: Duration| {
+            waited.set(waited.get() + duration);
+            async move { std::thread::sleep(duration) }
+        };
+        let generator = AsyncGenerator::from_generator(generator, timer);
+
+        let ulid = block_on(generator.generate_async());
+        assert!(ulid > previous);
+        assert!(waited.get() > Duration::ZERO);
+    }
+}
@@ -0,0 +1,200 @@
+//! Monotonic generation for historical timestamps arriving out of order.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::SystemTime;
+
+use crate::{OverflowError, Ulid};
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    previous: Ulid,
+    last_used: u64,
+}
+
+/// A Ulid generator for importing historical records
+///
+/// [`crate::Generator`] requires timestamps that never decrease. When backfilling, records arrive
+/// in any order, so this generator instead keeps a separate monotonic state for every millisecond
+/// it has seen. Ulids generated for the same millisecond are strictly increasing in the order
+/// they were generated, whatever happened to other milliseconds in between.
+///
+/// By default every millisecond is remembered. [`BackfillGenerator::with_max_buckets`] bounds the
+/// memory by forgetting the least recently used millisecond. Ulids generated for a forgotten
+/// millisecond start from a new random value and are no longer ordered after the earlier ones.
+///
+/// # Example
+/// ```rust
+/// use std::time::{Duration, SystemTime};
+/// use ulid::BackfillGenerator;
+///
+/// let monday = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// let tuesday = monday + Duration::from_secs(86_400);
+/// let mut generator = BackfillGenerator::new();
+///
+/// let ulid1 = generator.generate_from_datetime(tuesday).unwrap();
+/// let ulid2 = generator.generate_from_datetime(monday).unwrap();
+/// let ulid3 = generator.generate_from_datetime(tuesday).unwrap();
+///
+/// assert!(ulid2 < ulid1);
+/// assert!(ulid1 < ulid3);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BackfillGenerator {
+    buckets: HashMap<u64, Bucket>,
+    /// Milliseconds by the tick they were last used, only maintained when bounded
+    lru: BTreeMap<u64, u64>,
+    max_buckets: Option<usize>,
+    tick: u64,
+}
+
+impl BackfillGenerator {
+    /// Create a new backfill generator which remembers every millisecond
+    pub fn new() -> BackfillGenerator {
+        BackfillGenerator::default()
+    }
+
+    /// Create a new backfill generator which remembers at most `max_buckets` milliseconds
+    pub fn with_max_buckets(max_buckets: usize) -> BackfillGenerator {
+        BackfillGenerator {
+            max_buckets: Some(max_buckets.max(1)),
+            ..BackfillGenerator::default()
+        }
+    }
+
+    /// Generate a new Ulid matching the given DateTime. Each call is guaranteed to provide a Ulid
+    /// with a larger value than the last call for the same millisecond. If the random bits of that
+    /// millisecond would overflow, this method will return an error.
+    pub fn generate_from_datetime(&mut self, datetime: SystemTime) -> Result<Ulid, OverflowError> {
+        self.generate_from_datetime_with_source(datetime, &mut crate::time_utils::rng())
+    }
+
+    /// Generate a new Ulid with the given source matching the given DateTime. See
+    /// [`BackfillGenerator::generate_from_datetime`].
+    pub fn generate_from_datetime_with_source<R>(
+        &mut self,
+        datetime: SystemTime,
+        source: &mut R,
+    ) -> Result<Ulid, OverflowError>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        let candidate = Ulid::from_datetime_with_source(datetime, source);
+        let timestamp = candidate.timestamp_ms();
+        self.tick += 1;
+
+        let next = match self.buckets.get(&timestamp) {
+            Some(bucket) => match bucket.previous.increment() {
+                Ok(next) => next,
+                Err(_) => return Err(OverflowError::new(bucket.previous)),
+            },
+            None => {
+                self.evict();
+                candidate
+            }
+        };
+
+        let bucket = Bucket {
+            previous: next,
+            last_used: self.tick,
+        };
+        if let Some(old) = self.buckets.insert(timestamp, bucket) {
+            self.lru.remove(&old.last_used);
+        }
+        if self.max_buckets.is_some() {
+            self.lru.insert(self.tick, timestamp);
+        }
+        Ok(next)
+    }
+
+    /// Make room for a new millisecond if the generator is bounded
+    fn evict(&mut self) {
+        let max_buckets = match self.max_buckets {
+            Some(max_buckets) => max_buckets,
+            None => return,
+        };
+        while self.buckets.len() >= max_buckets {
+            match self.lru.pop_first() {
+                Some((_, timestamp)) => self.buckets.remove(&timestamp),
+                None => break,
+            };
+        }
+    }
+
+    /// The number of milliseconds currently remembered
+    pub fn len(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Returns true if no milliseconds are remembered
+    pub fn is_empty(&self) -> bool {
+        self.buckets.is_empty()
+    }
+
+    /// Forget every millisecond
+    pub fn clear(&mut self) {
+        self.buckets.clear();
+        self.lru.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_order_within_millisecond() {
+        let dt = SystemTime::now();
+        let mut generator = BackfillGenerator::new();
+
+        let mut previous = generator.generate_from_datetime(dt).unwrap();
+        for i in 0..100 {
+            let other = dt - Duration::from_millis(i % 7 + 1);
+            generator.generate_from_datetime(other).unwrap();
+
+            let next = generator.generate_from_datetime(dt).unwrap();
+            assert_eq!(next.0, previous.0 + 1);
+            previous = next;
+        }
+        assert_eq!(generator.len(), 8);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let dt = SystemTime::now();
+        let mut source = crate::StepRng::new(u64::MAX, 0);
+        let mut generator = BackfillGenerator::new();
+
+        let maxed = generator
+            .generate_from_datetime_with_source(dt, &mut source)
+            .unwrap();
+        let err = generator.generate_from_datetime(dt).unwrap_err();
+        assert_eq!(err.previous(), maxed);
+
+        assert!(generator
+            .generate_from_datetime(dt + Duration::from_millis(1))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_max_buckets() {
+        let dt = SystemTime::now();
+        let mut generator = BackfillGenerator::with_max_buckets(2);
+
+        let first = generator.generate_from_datetime(dt).unwrap();
+        generator
+            .generate_from_datetime(dt + Duration::from_millis(1))
+            .unwrap();
+        // keeps dt as the most recently used millisecond
+        assert_eq!(generator.generate_from_datetime(dt).unwrap().0, first.0 + 1);
+        generator
+            .generate_from_datetime(dt + Duration::from_millis(2))
+            .unwrap();
+
+        assert_eq!(generator.len(), 2);
+        assert_eq!(generator.generate_from_datetime(dt).unwrap().0, first.0 + 2);
+
+        generator.clear();
+        assert!(generator.is_empty());
+    }
+}
@@ -0,0 +1,145 @@
+//! Parallel generation of large numbers of Ulids.
+//!
+//! These functions are meant for fixtures and load tests that need millions or billions of
+//! unique, time-distributed Ulids. The work is split into fixed-size chunks and each chunk draws
+//! from its own random stream derived from the seed, so the output only depends on the seed and
+//! the inputs, never on the number of threads.
+//!
+//! The seeded random numbers are not suitable for Ulids that must be unpredictable. With 80 random
+//! bits per Ulid, collisions are astronomically unlikely but not impossible.
+//!
+//! # Example
+//! ```rust
+//! use std::time::{Duration, SystemTime};
+//! use ulid::bulk;
+//!
+//! let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+//! let range = start..start + Duration::from_secs(86_400);
+//!
+//! let ulids = bulk::generate_parallel_sorted(100_000, range.clone(), 42);
+//!
+//! assert_eq!(ulids.len(), 100_000);
+//! assert!(ulids.windows(2).all(|pair| pair[0] <= pair[1]));
+//! assert_eq!(ulids, bulk::generate_parallel_sorted(100_000, range, 42));
+//! ```
+
+use std::ops::Range;
+use std::time::{Duration, SystemTime};
+
+use rand::RngExt;
+use rayon::prelude::*;
+
+use crate::splitmix::SplitMix64;
+use crate::Ulid;
+
+/// The number of Ulids produced from one random stream
+const CHUNK_LEN: usize = 1 << 16;
+
+/// Generate `count` Ulids with timestamps spread uniformly over `time_range`
+///
+/// The result is in no particular order. The same seed and inputs always give the same Ulids.
+pub fn generate_parallel(count: usize, time_range: Range<SystemTime>, seed: u64) -> Vec<Ulid> {
+    let mut ulids = vec![Ulid::nil(); count];
+    fill_parallel(&mut ulids, time_range, seed);
+    ulids
+}
+
+/// Generate `count` Ulids with timestamps spread uniformly over `time_range`, sorted
+///
+/// See [`generate_parallel`].
+pub fn generate_parallel_sorted(
+    count: usize,
+    time_range: Range<SystemTime>,
+    seed: u64,
+) -> Vec<Ulid> {
+    let mut ulids = vec![Ulid::nil(); count];
+    fill_parallel_sorted(&mut ulids, time_range, seed);
+    ulids
+}
+
+/// Fill `out` with Ulids with timestamps spread uniformly over `time_range`
+///
+/// An empty range uses its start for every Ulid. The same seed and inputs always give the same
+/// Ulids.
+pub fn fill_parallel(out: &mut [Ulid], time_range: Range<SystemTime>, seed: u64) {
+    let start = timestamp_ms(time_range.start);
+    let span = timestamp_ms(time_range.end).saturating_sub(start).max(1);
+
+    out.par_chunks_mut(CHUNK_LEN)
+        .enumerate()
+        .for_each(|(index, chunk)| {
+            let mut rng = SplitMix64::for_stream(seed, index as u64);
+            for ulid in chunk {
+                let timestamp = start + rng.random_range(0..span);
+                let random =
+                    u128::from(rng.random::<u64>()) << 64 | u128::from(rng.random::<u64>());
+                *ulid = Ulid::from_parts(timestamp, random);
+            }
+        });
+}
+
+/// Fill `out` with Ulids with timestamps spread uniformly over `time_range`, sorted
+///
+/// See [`fill_parallel`].
+pub fn fill_parallel_sorted(out: &mut [Ulid], time_range: Range<SystemTime>, seed: u64) {
+    fill_parallel(out, time_range, seed);
+    out.par_sort_unstable();
+}
+
+fn timestamp_ms(datetime: SystemTime) -> u64 {
+    datetime
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn range() -> Range<SystemTime> {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        start..start + Duration::from_secs(3600)
+    }
+
+    fn with_threads<T: Send>(threads: usize, f: impl FnOnce() -> T + Send) -> T {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap()
+            .install(f)
+    }
+
+    #[test]
+    fn test_deterministic_across_thread_counts() {
+        let count = CHUNK_LEN * 3 + 17;
+        let one = with_threads(1, || generate_parallel(count, range(), 7));
+        let many = with_threads(8, || generate_parallel(count, range(), 7));
+        assert_eq!(one, many);
+        assert_ne!(one, generate_parallel(count, range(), 8));
+    }
+
+    #[test]
+    fn test_within_range_and_unique() {
+        let ulids = generate_parallel(CHUNK_LEN * 2, range(), 1);
+        let range = range();
+        assert!(ulids.iter().all(|ulid| range.contains(&ulid.datetime())));
+        let unique: HashSet<_> = ulids.iter().collect();
+        assert_eq!(unique.len(), ulids.len());
+    }
+
+    #[test]
+    fn test_sorted() {
+        let mut ulids = vec![Ulid::nil(); 1000];
+        fill_parallel_sorted(&mut ulids, range(), 3);
+        assert!(ulids.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn test_empty_range() {
+        let start = range().start;
+        let ulids = generate_parallel(10, start..start, 3);
+        assert!(ulids.iter().all(|ulid| ulid.datetime() == start));
+    }
+}
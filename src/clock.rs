@@ -0,0 +1,232 @@
+//! Clock sources for [`crate::Generator`].
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use crate::time_utils::Instant;
+
+/// A source of the current time for a [`crate::Generator`]
+///
+/// Any `Fn() -> SystemTime` is a `Clock`. See [`crate::Generator::set_clock`].
+pub trait Clock: Send + Sync {
+    /// The current time
+    fn now(&self) -> SystemTime;
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> SystemTime {
+        (**self).now()
+    }
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> SystemTime + Send + Sync,
+{
+    fn now(&self) -> SystemTime {
+        self()
+    }
+}
+
+/// The system wall clock, used by default
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        crate::time_utils::now()
+    }
+}
+
+/// The default fraction of elapsed time by which [`MonotonicClock`] corrects its drift
+const DEFAULT_SLEW_RATE: f64 = 0.01;
+
+/// A clock anchored to the wall clock once, which then advances with [`std::time::Instant`]
+///
+/// Reading [`SystemTime`] on every call lets an NTP step or a manual change of the system clock
+/// move the timestamps of new Ulids backwards or far forwards. This clock never jumps: it only
+/// moves forward, at the pace of the monotonic clock.
+///
+/// The wall clock is still compared on every read. Once the difference passes `threshold`, the
+/// clock slews towards the wall clock by running slightly faster or slower, by at most the slew
+/// rate as a fraction of the elapsed time, until the difference is gone. Timestamps therefore stay
+/// close to wall time without jumping.
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use ulid::{Generator, MonotonicClock};
+///
+/// let mut generator = Generator::new();
+/// generator.set_clock(MonotonicClock::new(Duration::from_millis(100)));
+///
+/// let ulid1 = generator.generate().unwrap();
+/// let ulid2 = generator.generate().unwrap();
+/// assert!(ulid1 < ulid2);
+/// ```
+#[derive(Debug)]
+pub struct MonotonicClock {
+    state: Mutex<MonotonicState>,
+    threshold: Duration,
+    slew_rate: f64,
+}
+
+#[derive(Debug)]
+struct MonotonicState {
+    /// The last time returned, as a duration since the unix epoch
+    last: Duration,
+    last_instant: Instant,
+    slewing: bool,
+}
+
+impl MonotonicClock {
+    /// Create a clock anchored to the current wall time, which slews once it drifts from the wall
+    /// clock by more than `threshold`
+    pub fn new(threshold: Duration) -> MonotonicClock {
+        MonotonicClock {
+            state: Mutex::new(MonotonicState {
+                last: since_epoch(crate::time_utils::now()),
+                last_instant: Instant::now(),
+                slewing: false,
+            }),
+            threshold,
+            slew_rate: DEFAULT_SLEW_RATE,
+        }
+    }
+
+    /// Sets the largest correction applied while slewing, as a fraction of the elapsed time
+    ///
+    /// The default of `0.01` corrects 10ms of drift per second. The rate is clamped to
+    /// `0.0..=0.99`, so the clock never stops or jumps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slew_rate` is NaN or infinite.
+    pub fn with_slew_rate(mut self, slew_rate: f64) -> MonotonicClock {
+        assert!(slew_rate.is_finite(), "slew rate must be finite");
+        self.slew_rate = slew_rate.clamp(0.0, 0.99);
+        self
+    }
+
+    /// The current time as seen by this clock, given the wall clock and monotonic clock readings
+    fn advance(&self, wall: SystemTime, instant: Instant) -> SystemTime {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let elapsed = instant.saturating_duration_since(state.last_instant);
+        let unslewed = state.last + elapsed;
+        let wall = since_epoch(wall);
+
+        let (ahead, drift) = if wall >= unslewed {
+            (true, wall - unslewed)
+        } else {
+            (false, unslewed - wall)
+        };
+        if drift > self.threshold {
+            state.slewing = true;
+        }
+
+        let mut next = unslewed;
+        if state.slewing {
+            let correction = elapsed.mul_f64(self.slew_rate);
+            if correction >= drift {
+                // caught up with the wall clock
+                state.slewing = false;
+                next = if ahead { wall } else { unslewed - drift };
+            } else if ahead {
+                next = unslewed + correction;
+            } else {
+                next = unslewed - correction;
+            }
+        }
+
+        state.last = next.max(state.last);
+        state.last_instant = instant;
+        SystemTime::UNIX_EPOCH + state.last
+    }
+}
+
+impl Clock for MonotonicClock {
+    fn now(&self) -> SystemTime {
+        self.advance(crate::time_utils::now(), Instant::now())
+    }
+}
+
+fn since_epoch(datetime: SystemTime) -> Duration {
+    datetime
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clock_at(wall: SystemTime, instant: Instant) -> MonotonicClock {
+        let clock = MonotonicClock::new(Duration::from_millis(100)).with_slew_rate(0.1);
+        {
+            let mut state = clock.state.lock().unwrap();
+            state.last = since_epoch(wall);
+            state.last_instant = instant;
+        }
+        clock
+    }
+
+    #[test]
+    fn test_follows_instant() {
+        let wall = SystemTime::now();
+        let start = Instant::now();
+        let clock = clock_at(wall, start);
+
+        let second = Duration::from_secs(1);
+        assert_eq!(clock.advance(wall + second, start + second), wall + second);
+    }
+
+    #[test]
+    fn test_backward_step_does_not_jump() {
+        let wall = SystemTime::now();
+        let start = Instant::now();
+        let clock = clock_at(wall, start);
+
+        // the wall clock steps back by an hour
+        let hour = Duration::from_secs(3600);
+        let second = Duration::from_secs(1);
+        let t1 = clock.advance(wall - hour + second, start + second);
+        assert_eq!(t1, wall + second - Duration::from_millis(100));
+
+        let mut previous = t1;
+        for i in 2..100 {
+            let elapsed = second * i;
+            let next = clock.advance(wall - hour + elapsed, start + elapsed);
+            assert!(next > previous);
+            assert_eq!(next - Duration::from_millis(900), previous);
+            previous = next;
+        }
+    }
+
+    #[test]
+    fn test_forward_step_is_slewed() {
+        let wall = SystemTime::now();
+        let start = Instant::now();
+        let clock = clock_at(wall, start);
+
+        // the wall clock steps forward by 150ms, then catches up over two seconds
+        let step = Duration::from_millis(150);
+        let second = Duration::from_secs(1);
+        let t1 = clock.advance(wall + step + second, start + second);
+        assert_eq!(t1, wall + second + Duration::from_millis(100));
+        let t2 = clock.advance(wall + step + second * 2, start + second * 2);
+        assert_eq!(t2, wall + step + second * 2);
+
+        // within the threshold, no correction
+        let t3 = clock.advance(wall + step + second * 3 + step / 2, start + second * 3);
+        assert_eq!(t3, wall + step + second * 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "slew rate must be finite")]
+    fn test_nan_slew_rate() {
+        let _ = MonotonicClock::new(Duration::from_millis(100)).with_slew_rate(f64::NAN);
+    }
+}
@@ -0,0 +1,213 @@
+//! Diesel support. The derives of `AsExpression` and `FromSqlRow` are on [`Ulid`] itself, so the
+//! SQL type of the column selects how a Ulid is stored:
+//!
+//! * `Uuid` (Postgres only) and `Binary` use the 16 big-endian bytes
+//! * `Text` uses the 26-character canonical string
+
+use std::io::Write;
+
+use diesel::backend::Backend;
+use diesel::deserialize::{self, FromSql};
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::{Binary, Text};
+
+use crate::sql::{ulid_from_slice, ulid_from_text};
+use crate::{Ulid, ULID_LEN};
+
+impl<DB> FromSql<Binary, DB> for Ulid
+where
+    DB: Backend,
+    Vec<u8>: FromSql<Binary, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        ulid_from_slice(&Vec::<u8>::from_sql(bytes)?)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for Ulid
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        ulid_from_text(&String::from_sql(bytes)?)
+    }
+}
+
+/// Backends whose bind values are written as raw bytes
+#[cfg(any(feature = "diesel-postgres", feature = "diesel-mysql"))]
+macro_rules! write_impls {
+    ($backend:ty) => {
+        impl ToSql<Binary, $backend> for Ulid {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, $backend>) -> serialize::Result {
+                out.write_all(&self.to_bytes())?;
+                Ok(IsNull::No)
+            }
+        }
+
+        impl ToSql<Text, $backend> for Ulid {
+            fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, $backend>) -> serialize::Result {
+                let mut buffer = [0; ULID_LEN];
+                out.write_all(self.array_to_str(&mut buffer).as_bytes())?;
+                Ok(IsNull::No)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "diesel-postgres")]
+mod pg {
+    use super::*;
+    use diesel::pg::{Pg, PgValue};
+    use diesel::sql_types::Uuid;
+
+    write_impls!(Pg);
+
+    impl FromSql<Uuid, Pg> for Ulid {
+        fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+            let bytes = value.as_bytes();
+            if bytes.len() != 16 {
+                return Err(format!(
+                    "invalid message length: expected 16 bytes for a ulid, got {}",
+                    bytes.len()
+                )
+                .into());
+            }
+            ulid_from_slice(bytes)
+        }
+    }
+
+    impl ToSql<Uuid, Pg> for Ulid {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+            out.write_all(&self.to_bytes())?;
+            Ok(IsNull::No)
+        }
+    }
+}
+
+#[cfg(feature = "diesel-mysql")]
+mod mysql {
+    use super::*;
+    use diesel::mysql::Mysql;
+
+    write_impls!(Mysql);
+}
+
+#[cfg(feature = "diesel-sqlite")]
+mod sqlite {
+    use super::*;
+    use diesel::sqlite::Sqlite;
+
+    impl ToSql<Binary, Sqlite> for Ulid {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+            out.set_value(self.to_bytes().to_vec());
+            Ok(IsNull::No)
+        }
+    }
+
+    impl ToSql<Text, Sqlite> for Ulid {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+            out.set_value(self.to_string());
+            Ok(IsNull::No)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use crate::Ulid;
+        use diesel::prelude::*;
+        use diesel::sql_types::{Binary, Text};
+        use diesel::sqlite::SqliteConnection;
+
+        diesel::table! {
+            events (id) {
+                id -> Binary,
+                parent -> Nullable<Text>,
+            }
+        }
+
+        #[derive(Debug, PartialEq, Queryable, Insertable)]
+        #[diesel(table_name = events)]
+        struct Event {
+            id: Ulid,
+            parent: Option<Ulid>,
+        }
+
+        fn connection() -> SqliteConnection {
+            let mut conn = SqliteConnection::establish(":memory:").unwrap();
+            diesel::sql_query("CREATE TABLE events (id BLOB PRIMARY KEY, parent TEXT)")
+                .execute(&mut conn)
+                .unwrap();
+            conn
+        }
+
+        #[test]
+        fn test_insert_and_load() {
+            let mut conn = connection();
+            let parent = Ulid::from_string("01D39ZY06FGSCTVN4T2V9PKHFZ").unwrap();
+            let rows = [
+                Event {
+                    id: Ulid::from_parts(1, 1),
+                    parent: None,
+                },
+                Event {
+                    id: Ulid::from_parts(2, 2),
+                    parent: Some(parent),
+                },
+            ];
+            diesel::insert_into(events::table)
+                .values(&rows[..])
+                .execute(&mut conn)
+                .unwrap();
+
+            let loaded: Vec<Event> = events::table.order(events::id).load(&mut conn).unwrap();
+            assert_eq!(loaded, rows);
+
+            let found: Ulid = events::table
+                .select(events::id)
+                .filter(events::parent.eq(parent))
+                .first(&mut conn)
+                .unwrap();
+            assert_eq!(found, rows[1].id);
+
+            let (bytes, text): (Vec<u8>, String) = diesel::sql_query(
+                "SELECT id AS bytes, parent AS text FROM events WHERE parent IS NOT NULL",
+            )
+            .get_result::<Raw>(&mut conn)
+            .map(|raw| (raw.bytes, raw.text))
+            .unwrap();
+            assert_eq!(bytes, rows[1].id.to_bytes());
+            assert_eq!(text, "01D39ZY06FGSCTVN4T2V9PKHFZ");
+        }
+
+        #[derive(QueryableByName)]
+        struct Raw {
+            #[diesel(sql_type = Binary)]
+            bytes: Vec<u8>,
+            #[diesel(sql_type = Text)]
+            text: String,
+        }
+
+        #[test]
+        fn test_invalid_value() {
+            let mut conn = connection();
+            diesel::sql_query("INSERT INTO events VALUES (x'0102', 'not a ulid')")
+                .execute(&mut conn)
+                .unwrap();
+
+            let err = events::table
+                .select(events::id)
+                .first::<Ulid>(&mut conn)
+                .unwrap_err();
+            let source = std::error::Error::source(&err);
+            assert_eq!(
+                source.unwrap().to_string(),
+                "invalid length: expected 16 bytes or 26 characters for a ulid, got 2 bytes"
+            );
+            assert!(events::table
+                .select(events::parent)
+                .first::<Option<Ulid>>(&mut conn)
+                .is_err());
+        }
+    }
+}
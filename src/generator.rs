@@ -1,7 +1,10 @@
 use std::fmt;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
+use std::{hint, thread};
 
-use crate::Ulid;
+use crate::{Clock, GeneratorStats, Ulid};
 
 /// A Ulid generator that provides monotonically increasing Ulids. This is implemented to match the
 /// reference generator's algorithm and it's [issues].
@@ -33,13 +36,503 @@ use crate::Ulid;
 #[derive(Debug, Clone)]
 pub struct Generator {
     previous: Ulid,
+    policy: OverflowPolicy,
+    regression: RegressionDetector,
+    stats: Option<GeneratorStats>,
+    clock: Option<SharedClock>,
+    headroom: u8,
+    fork_safe: bool,
+    /// The process which owns the state, or 0 if not yet known
+    pid: u32,
+}
+
+/// The strategy a [`Generator`] uses when the random bits of the current millisecond are exhausted
+///
+/// The policy is applied by [`Generator::try_generate`] and [`Generator::generate_blocking`]. The
+/// lower level `generate*` methods always return [`Overflow`] and leave the choice to the caller.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// Return an [`OverflowError`]
+    #[default]
+    Error,
+    /// Busy-wait until the clock moves past the overflowed millisecond
+    Spin,
+    /// Sleep in 1ms steps until the clock moves past the overflowed millisecond
+    Sleep,
+    /// Continue into the next millisecond with the random field starting at zero. See
+    /// [`Overflow::commit_overflow_increment`].
+    BorrowFuture,
+    /// Continue into the next millisecond with the random field starting at a random value. See
+    /// [`Overflow::commit_overflow_random`].
+    Random,
+}
+
+/// How a [`Generator`] reacts when the clock moves backwards by more than the configured threshold
+///
+/// See [`Generator::set_clock_regression`]. Like [`OverflowPolicy`], the policy is applied by
+/// [`Generator::try_generate`] and [`Generator::generate_blocking`]. The lower level `generate*`
+/// methods always continue, but still count the regression and call the hook.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClockRegressionPolicy {
+    /// Keep incrementing the last Ulid, so new Ulids stay pinned to its timestamp
+    #[default]
+    Continue,
+    /// Refuse to generate with [`GenerateError::ClockRegression`] until the clock catches up
+    Error,
+    /// Sleep until the clock catches up
+    Wait,
+}
+
+/// The longest single sleep of [`ClockRegressionPolicy::Wait`], so that a clock stepping forward
+/// again is noticed
+const MAX_REGRESSION_SLEEP: Duration = Duration::from_secs(1);
+
+#[derive(Clone)]
+struct SharedClock(Arc<dyn Clock>);
+
+impl fmt::Debug for SharedClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("SharedClock")
+    }
+}
+
+// Like the regression hook below, the clock is only read, so it cannot leave the generator in a
+// broken state when it panics.
+impl UnwindSafe for SharedClock {}
+impl RefUnwindSafe for SharedClock {}
+
+#[derive(Clone)]
+struct RegressionHook(Arc<dyn Fn(ClockRegression) + Send + Sync>);
+
+impl fmt::Debug for RegressionHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("RegressionHook")
+    }
+}
+
+// The hook is only called, never left half updated by the generator, so a panic in it cannot
+// break the generator's invariants. This keeps `Generator` unwind safe, as it was without a hook.
+impl UnwindSafe for RegressionHook {}
+impl RefUnwindSafe for RegressionHook {}
+
+#[derive(Debug, Clone)]
+struct RegressionDetector {
+    threshold: Option<Duration>,
+    policy: ClockRegressionPolicy,
+    hook: Option<RegressionHook>,
+    count: u64,
+    active: bool,
 }
 
 impl Generator {
     /// Create a new ulid generator for monotonically ordered ulids
     pub const fn new() -> Generator {
+        Generator::with_policy(OverflowPolicy::Error)
+    }
+
+    /// Create a new ulid generator which handles overflow with the given policy
+    ///
+    /// # Example
+    /// ```rust
+    /// use ulid::{Generator, OverflowPolicy};
+    ///
+    /// let mut generator = Generator::with_policy(OverflowPolicy::Sleep);
+    ///
+    /// let ulid1 = generator.try_generate().unwrap();
+    /// let ulid2 = generator.try_generate().unwrap();
+    ///
+    /// assert!(ulid1 < ulid2);
+    /// ```
+    pub const fn with_policy(policy: OverflowPolicy) -> Generator {
         Generator {
             previous: Ulid::nil(),
+            policy,
+            regression: RegressionDetector {
+                threshold: None,
+                policy: ClockRegressionPolicy::Continue,
+                hook: None,
+                count: 0,
+                active: false,
+            },
+            stats: None,
+            clock: None,
+            headroom: 0,
+            fork_safe: false,
+            pid: 0,
+        }
+    }
+
+    /// Create a new ulid generator with fork detection enabled. See [`Generator::set_fork_safe`].
+    pub(crate) const fn fork_safe() -> Generator {
+        let mut generator = Generator::new();
+        generator.fork_safe = true;
+        generator
+    }
+
+    /// Make sure every following Ulid is larger than `ulid`, without counting it as generated
+    pub(crate) fn advance_past(&mut self, ulid: Ulid) {
+        if ulid > self.previous {
+            self.previous = ulid;
+        }
+    }
+
+    /// Continue the sequence of another generator from its last Ulid
+    #[cfg(feature = "shared")]
+    pub(crate) fn resume(previous: Ulid, policy: OverflowPolicy) -> Generator {
+        Generator {
+            previous,
+            ..Generator::with_policy(policy)
+        }
+    }
+
+    /// Gets the overflow policy of this generator
+    pub const fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Sets the overflow policy of this generator
+    pub fn set_policy(&mut self, policy: OverflowPolicy) {
+        self.policy = policy;
+    }
+
+    /// Sets the number of top random bits cleared on the first Ulid of each millisecond
+    ///
+    /// A new millisecond starts from a random value, so it can overflow after only a few
+    /// increments if that value lands close to the maximum. Clearing the top `bits` bits
+    /// guarantees room for at least `2^80 - 2^(80 - bits)` increments before overflow, which is
+    /// already `2^79` for a single bit. The trade-off is entropy: the first Ulid of each
+    /// millisecond only has `80 - bits` random bits, which makes Ulids easier to guess and
+    /// collisions between independent generators more likely. The default of `0` keeps all 80
+    /// bits. Values above 80 are clamped.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::SystemTime;
+    /// use ulid::Generator;
+    ///
+    /// let mut generator = Generator::new();
+    /// generator.set_headroom(16);
+    ///
+    /// let dt = SystemTime::now();
+    /// let ulid = generator.generate_from_datetime(dt).unwrap();
+    ///
+    /// assert!(ulid.random() < 1 << 64);
+    /// ```
+    pub fn set_headroom(&mut self, bits: u8) {
+        self.headroom = bits.min(Ulid::RAND_BITS);
+    }
+
+    /// Gets the number of top random bits cleared on the first Ulid of each millisecond
+    pub const fn headroom(&self) -> u8 {
+        self.headroom
+    }
+
+    /// Clear the headroom bits of the first Ulid of a millisecond
+    fn with_headroom(&self, ulid: Ulid) -> Ulid {
+        let shift = u32::from(128 - Ulid::RAND_BITS + self.headroom);
+        let mask = u128::MAX.checked_shr(shift).unwrap_or(0);
+        Ulid::from_parts(ulid.timestamp_ms(), ulid.random() & mask)
+    }
+
+    /// Enables detection of `fork()`
+    ///
+    /// A child process created with `fork()` inherits a copy of the generator, so parent and
+    /// children would produce the same monotonic successors of the last Ulid within a millisecond.
+    /// With fork detection, the generator compares the current process id with the one that owns
+    /// its state before each Ulid. In a new process the inherited state is discarded and the
+    /// thread-local random number generator is reseeded, so the child starts its own sequence.
+    ///
+    /// Random numbers given with a `*_with_source` method are not reseeded. Only Ulids from the
+    /// same process are ordered with respect to each other.
+    ///
+    /// # Example
+    /// ```rust
+    /// use ulid::Generator;
+    ///
+    /// let mut generator = Generator::new();
+    /// generator.set_fork_safe(true);
+    ///
+    /// let ulid1 = generator.generate().unwrap();
+    /// let ulid2 = generator.generate().unwrap();
+    /// assert!(ulid1 < ulid2);
+    /// ```
+    pub fn set_fork_safe(&mut self, fork_safe: bool) {
+        self.fork_safe = fork_safe;
+        self.pid = if fork_safe { current_pid() } else { 0 };
+    }
+
+    /// Returns true if fork detection is enabled
+    pub const fn is_fork_safe(&self) -> bool {
+        self.fork_safe
+    }
+
+    /// Discard the state inherited from the parent if this process was forked
+    fn detect_fork(&mut self) {
+        if !self.fork_safe {
+            return;
+        }
+        let pid = current_pid();
+        if self.pid != pid {
+            if self.pid != 0 {
+                self.previous = Ulid::nil();
+                self.regression.active = false;
+                // a failure leaves the inherited stream, which is no worse than not reseeding
+                let _ = rand::rng().reseed();
+            }
+            self.pid = pid;
+        }
+    }
+
+    /// Enables clock regression detection
+    ///
+    /// The clock has regressed when it reads a time more than `threshold` before the timestamp of
+    /// the last generated Ulid. Each regression is counted once in
+    /// [`Generator::clock_regressions`] and reported to the hook set with
+    /// [`Generator::on_clock_regression`], no matter how many Ulids are generated before the clock
+    /// catches up. The `policy` selects what happens to the Ulids generated in the meantime.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::{Duration, SystemTime};
+    /// use ulid::{ClockRegressionPolicy, Generator};
+    ///
+    /// let mut generator = Generator::new();
+    /// generator.set_clock_regression(Duration::from_secs(1), ClockRegressionPolicy::Continue);
+    ///
+    /// let now = SystemTime::now();
+    /// let ulid1 = generator.generate_from_datetime(now).unwrap();
+    /// let ulid2 = generator.generate_from_datetime(now - Duration::from_secs(60)).unwrap();
+    ///
+    /// assert!(ulid1 < ulid2);
+    /// assert_eq!(generator.clock_regressions(), 1);
+    /// ```
+    pub fn set_clock_regression(&mut self, threshold: Duration, policy: ClockRegressionPolicy) {
+        self.regression.threshold = Some(threshold);
+        self.regression.policy = policy;
+    }
+
+    /// Sets a hook which is called with every clock regression detected by this generator
+    ///
+    /// Regressions are only detected once enabled with [`Generator::set_clock_regression`].
+    pub fn on_clock_regression<F>(&mut self, hook: F)
+    where
+        F: Fn(ClockRegression) + Send + Sync + 'static,
+    {
+        self.regression.hook = Some(RegressionHook(Arc::new(hook)));
+    }
+
+    /// Gets the clock regression policy of this generator
+    pub const fn clock_regression_policy(&self) -> ClockRegressionPolicy {
+        self.regression.policy
+    }
+
+    /// The number of clock regressions detected by this generator
+    pub const fn clock_regressions(&self) -> u64 {
+        self.regression.count
+    }
+
+    /// Sets the clock used by the methods which do not take a time, instead of the system clock
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::{Duration, SystemTime};
+    /// use ulid::Generator;
+    ///
+    /// let fixed = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    /// let mut generator = Generator::new();
+    /// generator.set_clock(move || fixed);
+    ///
+    /// assert_eq!(generator.generate().unwrap().datetime(), fixed);
+    /// ```
+    pub fn set_clock<C>(&mut self, clock: C)
+    where
+        C: Clock + 'static,
+    {
+        self.clock = Some(SharedClock(Arc::new(clock)));
+    }
+
+    /// The current time according to the generator's clock
+    pub(crate) fn now(&self) -> SystemTime {
+        match &self.clock {
+            Some(clock) => clock.0.now(),
+            None => crate::time_utils::now(),
+        }
+    }
+
+    /// Start collecting [`GeneratorStats`] for this generator
+    ///
+    /// Statistics already collected are kept.
+    pub fn enable_stats(&mut self) {
+        if self.stats.is_none() {
+            self.stats = Some(GeneratorStats::default());
+        }
+    }
+
+    /// The statistics collected by this generator, if enabled with [`Generator::enable_stats`]
+    pub fn stats(&self) -> Option<&GeneratorStats> {
+        self.stats.as_ref()
+    }
+
+    /// The last Ulid produced by this generator
+    pub(crate) const fn previous(&self) -> Ulid {
+        self.previous
+    }
+
+    /// Store the next Ulid produced by this generator
+    fn set_previous(&mut self, next: Ulid) {
+        if let Some(stats) = &mut self.stats {
+            stats.record_generated(next.timestamp_ms() == self.previous.timestamp_ms());
+        }
+        self.previous = next;
+    }
+
+    /// Check the given time against the last generated Ulid, recording a newly started regression
+    pub(crate) fn detect_clock_regression(
+        &mut self,
+        datetime: SystemTime,
+    ) -> Option<ClockRegression> {
+        let threshold = self.regression.threshold?;
+        let now_ms = datetime_ms(datetime);
+        let last_ms = u128::from(self.previous.timestamp_ms());
+        if now_ms + threshold.as_millis() >= last_ms {
+            self.regression.active = false;
+            return None;
+        }
+
+        let regression = ClockRegression {
+            previous: self.previous,
+            timestamp_ms: now_ms as u64,
+        };
+        let new = !self.regression.active;
+        if new {
+            self.regression.active = true;
+            self.regression.count += 1;
+            if let Some(hook) = &self.regression.hook {
+                (hook.0)(regression);
+            }
+        }
+        if let Some(stats) = &mut self.stats {
+            stats.record_clock_regression(regression.duration(), new);
+        }
+        Some(regression)
+    }
+
+    /// Generate a new Ulid, handling overflow with the generator's [`OverflowPolicy`]. Each call is
+    /// guaranteed to provide a Ulid with a larger value than the last call.
+    ///
+    /// Only [`OverflowPolicy::Error`] and [`ClockRegressionPolicy::Error`] can make this method
+    /// fail.
+    ///
+    /// # Example
+    /// ```rust
+    /// use ulid::{GenerateError, Generator};
+    ///
+    /// fn two_ulids(generator: &mut Generator) -> Result<(ulid::Ulid, ulid::Ulid), GenerateError> {
+    ///     Ok((generator.try_generate()?, generator.try_generate()?))
+    /// }
+    ///
+    /// let (ulid1, ulid2) = two_ulids(&mut Generator::new()).unwrap();
+    /// assert!(ulid1 < ulid2);
+    /// ```
+    pub fn try_generate(&mut self) -> Result<Ulid, GenerateError> {
+        self.try_generate_with_source(&mut crate::time_utils::rng())
+    }
+
+    /// Generate a new Ulid with the given source, handling overflow with the generator's
+    /// [`OverflowPolicy`]. Each call is guaranteed to provide a Ulid with a larger value than the
+    /// last call.
+    ///
+    /// Only [`OverflowPolicy::Error`] and [`ClockRegressionPolicy::Error`] can make this method
+    /// fail.
+    pub fn try_generate_with_source<R>(&mut self, source: &mut R) -> Result<Ulid, GenerateError>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        self.generate_with_policy(self.policy, self.regression.policy, source)
+    }
+
+    /// Generate a new Ulid, waiting for the next millisecond if the random bits would overflow.
+    /// Each call is guaranteed to provide a Ulid with a larger value than the last call.
+    ///
+    /// The generator's [`OverflowPolicy`] and [`ClockRegressionPolicy`] are used, except that
+    /// their `Error` variants are treated as [`OverflowPolicy::Sleep`] and
+    /// [`ClockRegressionPolicy::Wait`], so this method never fails.
+    ///
+    /// # Example
+    /// ```rust
+    /// use ulid::Generator;
+    ///
+    /// let mut generator = Generator::new();
+    ///
+    /// let ulid1 = generator.generate_blocking();
+    /// let ulid2 = generator.generate_blocking();
+    ///
+    /// assert!(ulid1 < ulid2);
+    /// ```
+    pub fn generate_blocking(&mut self) -> Ulid {
+        self.generate_blocking_with_source(&mut crate::time_utils::rng())
+    }
+
+    /// Generate a new Ulid with the given source, waiting for the next millisecond if the random
+    /// bits would overflow. See [`Generator::generate_blocking`].
+    pub fn generate_blocking_with_source<R>(&mut self, source: &mut R) -> Ulid
+    where
+        R: rand::Rng + ?Sized,
+    {
+        let policy = match self.policy {
+            OverflowPolicy::Error => OverflowPolicy::Sleep,
+            policy => policy,
+        };
+        let regression_policy = match self.regression.policy {
+            ClockRegressionPolicy::Error => ClockRegressionPolicy::Wait,
+            policy => policy,
+        };
+        match self.generate_with_policy(policy, regression_policy, source) {
+            Ok(ulid) => ulid,
+            Err(_) => unreachable!("only the Error policies can fail"),
+        }
+    }
+
+    fn generate_with_policy<R>(
+        &mut self,
+        policy: OverflowPolicy,
+        regression_policy: ClockRegressionPolicy,
+        source: &mut R,
+    ) -> Result<Ulid, GenerateError>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        if regression_policy != ClockRegressionPolicy::Continue {
+            while let Some(regression) = self.detect_clock_regression(self.now()) {
+                if regression_policy == ClockRegressionPolicy::Error {
+                    return Err(GenerateError::ClockRegression(regression));
+                }
+                thread::sleep(regression.duration().min(MAX_REGRESSION_SLEEP));
+            }
+        }
+
+        match policy {
+            OverflowPolicy::Error => self
+                .generate_with_source(source)
+                .map_err(|overflow| GenerateError::Overflow(overflow.into())),
+            OverflowPolicy::Spin | OverflowPolicy::Sleep => loop {
+                if let Ok(ulid) = self.generate_with_source(source) {
+                    return Ok(ulid);
+                }
+                if policy == OverflowPolicy::Spin {
+                    hint::spin_loop();
+                } else {
+                    thread::sleep(Duration::from_millis(1));
+                }
+            },
+            OverflowPolicy::BorrowFuture => Ok(match self.generate_with_source(source) {
+                Ok(ulid) => ulid,
+                Err(overflow) => overflow.commit_overflow_increment(),
+            }),
+            OverflowPolicy::Random => Ok(match self.generate_with_source(source) {
+                Ok(ulid) => ulid,
+                Err(overflow) => overflow.commit_overflow_random_with_source(source),
+            }),
         }
     }
 
@@ -56,7 +549,7 @@ impl Generator {
     /// assert!(ulid1 < ulid2);
     /// ```
     pub fn generate(&mut self) -> Result<Ulid, Overflow<'_>> {
-        self.generate_from_datetime(crate::time_utils::now())
+        self.generate_from_datetime(self.now())
     }
 
     /// Generate a new Ulid matching the given DateTime.
@@ -78,7 +571,7 @@ impl Generator {
     /// assert!(ulid1 < ulid2);
     /// ```
     pub fn generate_from_datetime(&mut self, datetime: SystemTime) -> Result<Ulid, Overflow<'_>> {
-        self.generate_from_datetime_with_source(datetime, &mut rand::rng())
+        self.generate_from_datetime_with_source(datetime, &mut crate::time_utils::rng())
     }
 
     /// Generate a new monotonic increasing Ulid with the given source
@@ -104,7 +597,7 @@ impl Generator {
     where
         R: rand::Rng + ?Sized,
     {
-        self.generate_from_datetime_with_source(crate::time_utils::now(), source)
+        self.generate_from_datetime_with_source(self.now(), source)
     }
 
     /// Generate a new monotonic increasing Ulid with the given source matching the given DateTime
@@ -135,24 +628,28 @@ impl Generator {
     where
         R: rand::Rng + ?Sized,
     {
+        self.detect_fork();
+        self.detect_clock_regression(datetime);
+
         let last_ms = self.previous.timestamp_ms();
         // maybe time went backward, or it is the same ms.
         // increment instead of generating a new random so that it is monotonic
-        if datetime
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap_or(Duration::ZERO)
-            .as_millis()
-            <= u128::from(last_ms)
-        {
+        if datetime_ms(datetime) <= u128::from(last_ms) {
             if let Ok(next) = self.previous.increment() {
-                self.previous = next;
+                if let Some(stats) = &mut self.stats {
+                    stats.record_increment();
+                }
+                self.set_previous(next);
                 return Ok(next);
             } else {
+                if let Some(stats) = &mut self.stats {
+                    stats.record_overflow();
+                }
                 return Err(Overflow { generator: self });
             }
         }
-        let next = Ulid::from_datetime_with_source(datetime, source);
-        self.previous = next;
+        let next = self.with_headroom(Ulid::from_datetime_with_source(datetime, source));
+        self.set_previous(next);
         Ok(next)
     }
 }
@@ -163,6 +660,21 @@ impl Default for Generator {
     }
 }
 
+/// The id of the current process, or 0 where processes cannot fork
+fn current_pid() -> u32 {
+    #[cfg(unix)]
+    return std::process::id();
+    #[cfg(not(unix))]
+    return 0;
+}
+
+fn datetime_ms(datetime: SystemTime) -> u128 {
+    datetime
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_millis()
+}
+
 /// Would overflow into the next millisecond
 #[derive(Debug)]
 pub struct Overflow<'a> {
@@ -170,6 +682,11 @@ pub struct Overflow<'a> {
 }
 
 impl<'a> Overflow<'a> {
+    /// The last Ulid produced by the generator before it overflowed
+    pub fn previous(&self) -> Ulid {
+        self.generator.previous
+    }
+
     /// Commit an overflow value into the generator via increment. The generator will be incremented
     /// into the next millisecond with the random field starting at zero.
     pub fn commit_overflow_increment(self) -> Ulid {
@@ -177,14 +694,17 @@ impl<'a> Overflow<'a> {
             Ok(next) => next,
             Err(next) => next,
         };
-        self.generator.previous = next;
+        if let Some(stats) = &mut self.generator.stats {
+            stats.record_overflow_increment();
+        }
+        self.generator.set_previous(next);
         next
     }
 
     /// Commit an overflow value into the generator via a random value. The generator will be
     /// incremented into the next millisecond with the random field starting at a random value.
     pub fn commit_overflow_random(self) -> Ulid {
-        self.commit_overflow_random_with_source(&mut rand::rng())
+        self.commit_overflow_random_with_source(&mut crate::time_utils::rng())
     }
 
     /// Commit an overflow value into the generator via a random value. The generator will be
@@ -192,14 +712,19 @@ impl<'a> Overflow<'a> {
     /// random value will be generated from the given source.
     pub fn commit_overflow_random_with_source<R>(self, source: &mut R) -> Ulid
     where
-        R: rand::Rng,
+        R: rand::Rng + ?Sized,
     {
         let inc = match self.generator.previous.increment() {
             Ok(inc) => inc,
             Err(inc) => inc,
         };
-        let next = Ulid::from_datetime_with_source(inc.datetime(), source);
-        self.generator.previous = next;
+        let next = self
+            .generator
+            .with_headroom(Ulid::from_datetime_with_source(inc.datetime(), source));
+        if let Some(stats) = &mut self.generator.stats {
+            stats.record_overflow_random();
+        }
+        self.generator.set_previous(next);
         next
     }
 }
@@ -212,7 +737,112 @@ impl fmt::Display for Overflow<'_> {
     }
 }
 
+/// Would overflow into the next millisecond
+///
+/// Unlike [`Overflow`] this does not borrow the generator, so it can be returned through `?` or
+/// sent across threads. The overflow can no longer be committed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OverflowError {
+    previous: Ulid,
+}
+
+impl OverflowError {
+    pub(crate) const fn new(previous: Ulid) -> OverflowError {
+        OverflowError { previous }
+    }
+
+    /// The last Ulid produced by the generator before it overflowed
+    pub const fn previous(&self) -> Ulid {
+        self.previous
+    }
+}
+
+impl From<Overflow<'_>> for OverflowError {
+    fn from(overflow: Overflow<'_>) -> Self {
+        OverflowError {
+            previous: overflow.generator.previous,
+        }
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "Ulid random bits would overflow")
+    }
+}
+
+/// The clock moved backwards past the last Ulid produced by a [`Generator`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClockRegression {
+    previous: Ulid,
+    timestamp_ms: u64,
+}
+
+impl ClockRegression {
+    /// The last Ulid produced by the generator before the clock moved backwards
+    pub const fn previous(&self) -> Ulid {
+        self.previous
+    }
+
+    /// The time read from the clock as a unix timestamp in milliseconds
+    pub const fn timestamp_ms(&self) -> u64 {
+        self.timestamp_ms
+    }
+
+    /// How far the clock moved backwards
+    pub const fn duration(&self) -> Duration {
+        Duration::from_millis(self.previous.timestamp_ms() - self.timestamp_ms)
+    }
+}
+
+impl std::error::Error for ClockRegression {}
+
+impl fmt::Display for ClockRegression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "clock moved backwards by {}ms",
+            self.duration().as_millis()
+        )
+    }
+}
+
+/// An error that can occur when generating a Ulid with [`Generator::try_generate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GenerateError {
+    /// The random bits would overflow into the next millisecond
+    Overflow(OverflowError),
+    /// The clock moved backwards by more than the configured threshold
+    ClockRegression(ClockRegression),
+}
+
+impl From<OverflowError> for GenerateError {
+    fn from(err: OverflowError) -> Self {
+        GenerateError::Overflow(err)
+    }
+}
+
+impl From<ClockRegression> for GenerateError {
+    fn from(err: ClockRegression) -> Self {
+        GenerateError::ClockRegression(err)
+    }
+}
+
+impl std::error::Error for GenerateError {}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            GenerateError::Overflow(err) => err.fmt(f),
+            GenerateError::ClockRegression(err) => err.fmt(f),
+        }
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::identity_op)]
 mod tests {
     use super::*;
     use std::time::Duration;
@@ -259,6 +889,7 @@ mod tests {
 
         let mut generator = Generator {
             previous: maxed_random,
+            ..Generator::new()
         };
         let err_overflow = generator
             .generate_from_datetime(maxed_random.datetime())
@@ -284,6 +915,7 @@ mod tests {
 
         let mut generator = Generator {
             previous: maxed_random,
+            ..Generator::new()
         };
         let err_overflow = generator
             .generate_from_datetime(maxed_random.datetime())
@@ -309,6 +941,7 @@ mod tests {
 
         let mut generator = Generator {
             previous: maxed_random,
+            ..Generator::new()
         };
         let err_overflow = generator
             .generate_from_datetime(maxed_random.datetime())
@@ -322,4 +955,260 @@ mod tests {
         assert!(next_ulid > maxed_random);
         assert_eq!(generator.previous, next_ulid);
     }
+
+    fn maxed_random_at(datetime: SystemTime) -> Ulid {
+        let ulid = Ulid::from_datetime(datetime);
+        Ulid::from_parts(ulid.timestamp_ms(), crate::bitmask!(Ulid::RAND_BITS))
+    }
+
+    #[test]
+    fn test_policy_error() {
+        let maxed_random = maxed_random_at(SystemTime::now() + Duration::from_secs(3600));
+        let mut generator = Generator {
+            previous: maxed_random,
+            ..Generator::new()
+        };
+
+        let err = match generator.try_generate() {
+            Err(GenerateError::Overflow(err)) => err,
+            other => panic!("expected overflow, got {:?}", other),
+        };
+        assert_eq!(err.previous(), maxed_random);
+        assert_eq!(generator.previous, maxed_random);
+
+        let err: OverflowError = generator.generate().unwrap_err().into();
+        assert_eq!(err.previous(), maxed_random);
+    }
+
+    #[test]
+    fn test_policy_borrow_future() {
+        let maxed_random = maxed_random_at(SystemTime::now() + Duration::from_secs(3600));
+        let mut generator = Generator {
+            previous: maxed_random,
+            ..Generator::with_policy(OverflowPolicy::BorrowFuture)
+        };
+
+        let next_ulid = generator.try_generate().unwrap();
+        assert_eq!(next_ulid.timestamp_ms(), maxed_random.timestamp_ms() + 1);
+        assert_eq!(next_ulid.random(), 0);
+    }
+
+    #[test]
+    fn test_policy_random() {
+        let maxed_random = maxed_random_at(SystemTime::now() + Duration::from_secs(3600));
+        let mut generator = Generator {
+            previous: maxed_random,
+            ..Generator::with_policy(OverflowPolicy::Random)
+        };
+
+        let mut source = crate::StepRng::new(42, 0);
+        let next_ulid = generator.try_generate_with_source(&mut source).unwrap();
+        assert_eq!(next_ulid.timestamp_ms(), maxed_random.timestamp_ms() + 1);
+        assert_eq!(next_ulid.random(), 42 << 64 | 42);
+    }
+
+    #[test]
+    fn test_policy_waits_for_next_millisecond() {
+        for policy in [OverflowPolicy::Spin, OverflowPolicy::Sleep] {
+            let maxed_random = maxed_random_at(SystemTime::now() + Duration::from_millis(5));
+            let mut generator = Generator::with_policy(policy);
+            generator.previous = maxed_random;
+
+            let next_ulid = generator.try_generate().unwrap();
+            assert!(next_ulid.timestamp_ms() > maxed_random.timestamp_ms());
+        }
+    }
+
+    #[test]
+    fn test_generate_blocking_never_fails() {
+        let maxed_random = maxed_random_at(SystemTime::now() + Duration::from_millis(5));
+        let mut generator = Generator::new();
+        generator.previous = maxed_random;
+
+        let next_ulid = generator.generate_blocking();
+        assert!(next_ulid > maxed_random);
+        assert_eq!(generator.policy(), OverflowPolicy::Error);
+    }
+
+    #[test]
+    fn test_clock_regression_is_counted_once() {
+        let reported = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut generator = Generator::new();
+        generator.set_clock_regression(Duration::from_millis(10), ClockRegressionPolicy::Continue);
+        let hook_reported = reported.clone();
+        generator
+            .on_clock_regression(move |regression| hook_reported.lock().unwrap().push(regression));
+
+        let now = SystemTime::now();
+        let ulid1 = generator.generate_from_datetime(now).unwrap();
+        // within the threshold
+        let ulid2 = generator
+            .generate_from_datetime(now - Duration::from_millis(10))
+            .unwrap();
+        assert_eq!(generator.clock_regressions(), 0);
+
+        let ulid3 = generator
+            .generate_from_datetime(now - Duration::from_secs(60))
+            .unwrap();
+        let ulid4 = generator
+            .generate_from_datetime(now - Duration::from_secs(30))
+            .unwrap();
+        assert!(ulid1 < ulid2 && ulid2 < ulid3 && ulid3 < ulid4);
+        assert_eq!(generator.clock_regressions(), 1);
+
+        // caught up, then regressed again
+        generator.generate_from_datetime(now).unwrap();
+        generator
+            .generate_from_datetime(now - Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(generator.clock_regressions(), 2);
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 2);
+        assert_eq!(reported[0].previous(), ulid2);
+        assert_eq!(reported[0].duration(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_clock_regression_policy_error() {
+        let mut generator = Generator::new();
+        generator.set_clock_regression(Duration::from_secs(1), ClockRegressionPolicy::Error);
+        let ahead = SystemTime::now() + Duration::from_secs(3600);
+        let previous = generator.generate_from_datetime(ahead).unwrap();
+
+        let regression = match generator.try_generate() {
+            Err(GenerateError::ClockRegression(regression)) => regression,
+            other => panic!("expected clock regression, got {:?}", other),
+        };
+        assert_eq!(regression.previous(), previous);
+        assert!(regression.duration() > Duration::from_secs(3590));
+        assert!(generator.try_generate().is_err());
+        assert_eq!(generator.clock_regressions(), 1);
+    }
+
+    #[test]
+    fn test_unwind_safe() {
+        fn assert_unwind_safe<T: UnwindSafe + RefUnwindSafe>() {}
+        assert_unwind_safe::<RegressionDetector>();
+        assert_unwind_safe::<SharedClock>();
+        assert_unwind_safe::<Generator>();
+    }
+
+    #[test]
+    fn test_clock_regression_policy_wait() {
+        let mut generator = Generator::new();
+        generator.set_clock_regression(Duration::ZERO, ClockRegressionPolicy::Wait);
+        let ahead = SystemTime::now() + Duration::from_millis(20);
+        let previous = generator.generate_from_datetime(ahead).unwrap();
+
+        let ulid = generator.try_generate().unwrap();
+        assert!(ulid > previous);
+        assert!(SystemTime::now() >= previous.datetime());
+    }
+
+    #[test]
+    fn test_stats_disabled_by_default() {
+        let mut generator = Generator::new();
+        generator.generate().unwrap();
+        assert!(generator.stats().is_none());
+    }
+
+    #[test]
+    fn test_stats() {
+        let dt = SystemTime::now();
+        let max_random = crate::bitmask!(Ulid::RAND_BITS);
+        let mut generator = Generator {
+            previous: Ulid::from_datetime(dt),
+            ..Generator::new()
+        };
+        generator.previous = Ulid::from_parts(generator.previous.timestamp_ms(), max_random - 2);
+        generator.enable_stats();
+        generator.set_clock_regression(Duration::from_millis(10), ClockRegressionPolicy::Continue);
+
+        generator.generate_from_datetime(dt).unwrap();
+        generator.generate_from_datetime(dt).unwrap();
+        assert!(generator.generate_from_datetime(dt).is_err());
+        assert!(generator.generate_from_datetime(dt).is_err());
+
+        // the clock moves on, with random bits that are about to overflow again
+        let mut source = crate::StepRng::new(u64::MAX, 0);
+        let later = dt + Duration::from_millis(5);
+        let ulid = generator
+            .generate_from_datetime_with_source(later, &mut source)
+            .unwrap();
+        assert_eq!(ulid.random(), max_random);
+        generator
+            .generate_from_datetime(later)
+            .unwrap_err()
+            .commit_overflow_increment();
+
+        generator
+            .generate_from_datetime(dt - Duration::from_secs(2))
+            .unwrap();
+
+        let stats = generator.stats().unwrap();
+        assert_eq!(stats.generated(), 5);
+        assert_eq!(stats.increments(), 3);
+        assert_eq!(stats.overflows(), 2);
+        assert_eq!(stats.overflow_increments(), 1);
+        assert_eq!(stats.overflow_randoms(), 0);
+        assert_eq!(stats.overflow_waits(), 1);
+        assert_eq!(stats.clock_regressions(), 1);
+        assert!(stats.max_clock_regression() > Duration::from_secs(2));
+        assert_eq!(stats.max_burst(), 2);
+    }
+
+    #[test]
+    fn test_headroom() {
+        let dt = SystemTime::now();
+        let mut source = crate::StepRng::new(u64::MAX, 0);
+        let mut generator = Generator::new();
+        generator.set_headroom(8);
+        assert_eq!(generator.headroom(), 8);
+
+        let ulid = generator
+            .generate_from_datetime_with_source(dt, &mut source)
+            .unwrap();
+        assert_eq!(ulid.random(), crate::bitmask!(72));
+
+        generator.previous =
+            Ulid::from_parts(ulid.timestamp_ms(), crate::bitmask!(Ulid::RAND_BITS));
+        let overflow = generator
+            .generate_from_datetime(dt)
+            .unwrap_err()
+            .commit_overflow_random_with_source(&mut source);
+        assert_eq!(overflow.random(), crate::bitmask!(72));
+
+        generator.set_headroom(u8::MAX);
+        assert_eq!(generator.headroom(), Ulid::RAND_BITS);
+        let later = dt + Duration::from_millis(5);
+        assert_eq!(generator.generate_from_datetime(later).unwrap().random(), 0);
+    }
+
+    #[test]
+    fn test_fork_detection() {
+        let dt = SystemTime::now();
+        let mut generator = Generator::new();
+        generator.set_fork_safe(true);
+        assert!(generator.is_fork_safe());
+
+        let mut source = crate::StepRng::new(0, 0);
+        let first = generator
+            .generate_from_datetime_with_source(dt, &mut source)
+            .unwrap();
+        assert_eq!(
+            generator
+                .generate_from_datetime_with_source(dt, &mut source)
+                .unwrap(),
+            first.increment().unwrap()
+        );
+
+        // pretend the state was inherited from a parent process
+        generator.pid = current_pid().wrapping_add(1);
+        let child = generator
+            .generate_from_datetime_with_source(dt, &mut source)
+            .unwrap();
+        assert_eq!(child, first);
+        assert_eq!(generator.pid, current_pid());
+    }
 }
@@ -0,0 +1,61 @@
+//! A monotonic generator shared by the whole process.
+
+use std::sync::Mutex;
+
+use crate::{Generator, Ulid};
+
+/// Fork-safe, so that pre-forked worker processes do not continue the parent's sequence
+static GLOBAL: Mutex<Generator> = Mutex::new(Generator::fork_safe());
+
+impl Ulid {
+    /// Creates a new Ulid from a generator shared by the whole process
+    ///
+    /// Each call is guaranteed to provide a Ulid with a larger value than the last call from any
+    /// thread of this process. The generator waits for the next millisecond when the random bits
+    /// overflow, as with [`Generator::generate_blocking`]. It detects `fork()`, see
+    /// [`Generator::set_fork_safe`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use ulid::Ulid;
+    ///
+    /// let ulid1 = Ulid::generate_monotonic();
+    /// let ulid2 = Ulid::generate_monotonic();
+    ///
+    /// assert!(ulid1 < ulid2);
+    /// ```
+    pub fn generate_monotonic() -> Ulid {
+        GLOBAL
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .generate_blocking()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_monotonic() {
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                std::thread::spawn(|| {
+                    let mut ulids: Vec<_> = (0..1000).map(|_| Ulid::generate_monotonic()).collect();
+                    assert!(ulids.windows(2).all(|pair| pair[0] < pair[1]));
+                    ulids.dedup();
+                    ulids
+                })
+            })
+            .collect();
+
+        let mut ulids: Vec<_> = threads
+            .into_iter()
+            .flat_map(|thread| thread.join().unwrap())
+            .collect();
+        ulids.sort();
+        ulids.dedup();
+        assert_eq!(ulids.len(), 4000);
+        assert!(GLOBAL.lock().unwrap().is_fork_safe());
+    }
+}
@@ -0,0 +1,183 @@
+//! Conversions to the byte layouts of SQL Server and .NET GUIDs.
+
+use crate::Ulid;
+
+/// The index of the Ulid byte at each position of the SQL Server layout
+///
+/// SQL Server compares `uniqueidentifier` values by bytes 10 to 15 first, then 8 to 9, 6 to 7,
+/// 4 to 5 and finally 0 to 3, so the most significant bytes of the Ulid go last.
+const SQLSERVER_ORDER: [usize; 16] = [12, 13, 14, 15, 10, 11, 8, 9, 6, 7, 0, 1, 2, 3, 4, 5];
+
+/// The index of the Ulid byte at each position of the .NET layout, whose first three fields are
+/// little-endian
+const DOTNET_ORDER: [usize; 16] = [3, 2, 1, 0, 5, 4, 7, 6, 8, 9, 10, 11, 12, 13, 14, 15];
+
+const fn permute(bytes: [u8; 16], order: &[usize; 16]) -> [u8; 16] {
+    let mut permuted = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        permuted[i] = bytes[order[i]];
+        i += 1;
+    }
+    permuted
+}
+
+const fn unpermute(permuted: [u8; 16], order: &[usize; 16]) -> [u8; 16] {
+    let mut bytes = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        bytes[order[i]] = permuted[i];
+        i += 1;
+    }
+    bytes
+}
+
+impl Ulid {
+    /// Returns the bytes of a SQL Server `uniqueidentifier` which sorts in the same order as the
+    /// Ulid
+    ///
+    /// The bytes are in the layout SQL Server stores, as used by
+    /// `CAST(0x... AS uniqueidentifier)` and the .NET `Guid(byte[])` constructor. The timestamp
+    /// goes into the last six bytes, which SQL Server compares first, so Ulids inserted in order
+    /// are appended to a clustered index. The GUID does not display like the Ulid converted to a
+    /// UUID.
+    ///
+    /// # Example
+    /// ```
+    /// use ulid::Ulid;
+    ///
+    /// let ulid = Ulid::from_parts(0x0102_0304_0506, 0);
+    /// let bytes = ulid.to_sqlserver_guid_bytes();
+    ///
+    /// assert_eq!(bytes[10..], [1, 2, 3, 4, 5, 6]);
+    /// assert_eq!(Ulid::from_sqlserver_guid_bytes(bytes), ulid);
+    /// ```
+    pub const fn to_sqlserver_guid_bytes(&self) -> [u8; 16] {
+        permute(self.to_bytes(), &SQLSERVER_ORDER)
+    }
+
+    /// Creates a Ulid from the bytes of a SQL Server `uniqueidentifier` made by
+    /// [`Ulid::to_sqlserver_guid_bytes`]
+    ///
+    /// # Example
+    /// ```
+    /// use ulid::Ulid;
+    ///
+    /// let ulid = Ulid::from_parts(1_700_000_000_000, 42);
+    ///
+    /// assert_eq!(Ulid::from_sqlserver_guid_bytes(ulid.to_sqlserver_guid_bytes()), ulid);
+    /// ```
+    pub const fn from_sqlserver_guid_bytes(bytes: [u8; 16]) -> Ulid {
+        Ulid::from_bytes(unpermute(bytes, &SQLSERVER_ORDER))
+    }
+
+    /// Returns the bytes of the .NET `Guid` which displays like the Ulid converted to a UUID
+    ///
+    /// .NET stores the first three fields of a GUID little-endian, as returned by
+    /// `Guid.ToByteArray()`. The order of the bytes otherwise matches [`Ulid::to_bytes`].
+    ///
+    /// # Example
+    /// ```
+    /// use ulid::Ulid;
+    ///
+    /// // the UUID 0168d3ff-00cf-8659-add4-9a16d369c5ff
+    /// let ulid = Ulid::from_string("01D39ZY06FGSCTVN4T2V9PKHFZ").unwrap();
+    ///
+    /// assert_eq!(
+    ///     ulid.to_dotnet_guid_bytes(),
+    ///     [
+    ///         0xff, 0xd3, 0x68, 0x01, 0xcf, 0x00, 0x59, 0x86, 0xad, 0xd4, 0x9a, 0x16, 0xd3, 0x69,
+    ///         0xc5, 0xff
+    ///     ]
+    /// );
+    /// ```
+    pub const fn to_dotnet_guid_bytes(&self) -> [u8; 16] {
+        permute(self.to_bytes(), &DOTNET_ORDER)
+    }
+
+    /// Creates a Ulid from the bytes of a .NET `Guid`, as returned by `Guid.ToByteArray()`
+    ///
+    /// # Example
+    /// ```
+    /// use ulid::Ulid;
+    ///
+    /// let ulid = Ulid::from_parts(1_700_000_000_000, 42);
+    ///
+    /// assert_eq!(Ulid::from_dotnet_guid_bytes(ulid.to_dotnet_guid_bytes()), ulid);
+    /// ```
+    pub const fn from_dotnet_guid_bytes(bytes: [u8; 16]) -> Ulid {
+        Ulid::from_bytes(unpermute(bytes, &DOTNET_ORDER))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    /// The bytes of a `uniqueidentifier` in the order SQL Server compares them
+    fn sqlserver_sort_key(bytes: [u8; 16]) -> [u8; 16] {
+        const COMPARE_ORDER: [usize; 16] = [10, 11, 12, 13, 14, 15, 8, 9, 6, 7, 4, 5, 0, 1, 2, 3];
+        permute(bytes, &COMPARE_ORDER)
+    }
+
+    fn ulids() -> Vec<Ulid> {
+        let mut ulids: Vec<Ulid> = (0..1000).map(|_| Ulid::generate()).collect();
+        ulids.extend((0..16).map(|byte| Ulid(1 << (byte * 8))));
+        ulids.extend([Ulid::nil(), Ulid(u128::MAX)]);
+        ulids
+    }
+
+    #[test]
+    fn test_sqlserver_order() {
+        let mut ulids = ulids();
+        ulids.sort();
+
+        let mut guids: Vec<[u8; 16]> = ulids.iter().map(Ulid::to_sqlserver_guid_bytes).collect();
+        guids.sort_by_key(|&bytes| sqlserver_sort_key(bytes));
+
+        let sorted: Vec<Ulid> = guids
+            .into_iter()
+            .map(Ulid::from_sqlserver_guid_bytes)
+            .collect();
+        assert_eq!(sorted, ulids);
+    }
+
+    #[test]
+    fn test_round_trips() {
+        for ulid in ulids() {
+            assert_eq!(
+                Ulid::from_sqlserver_guid_bytes(ulid.to_sqlserver_guid_bytes()),
+                ulid
+            );
+            assert_eq!(
+                Ulid::from_dotnet_guid_bytes(ulid.to_dotnet_guid_bytes()),
+                ulid
+            );
+        }
+    }
+
+    #[test]
+    fn test_dotnet_layout() {
+        let ulid = Ulid::from_bytes([
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+            0xee, 0xff,
+        ]);
+        assert_eq!(
+            ulid.to_dotnet_guid_bytes(),
+            [
+                0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+                0xee, 0xff,
+            ]
+        );
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_dotnet_matches_uuid_le() {
+        for ulid in ulids() {
+            let uuid = uuid::Uuid::from(ulid);
+            assert_eq!(ulid.to_dotnet_guid_bytes(), uuid.to_bytes_le());
+            assert_eq!(Ulid::from_dotnet_guid_bytes(uuid.to_bytes_le()), ulid);
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! A hybrid logical clock generator for causally ordered Ulids.
+
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use crate::{GenerateError, Generator, Overflow, Ulid};
+
+/// A monotonic Ulid generator which also orders Ulids after the remote Ulids it has observed
+///
+/// This follows the idea of a hybrid logical clock: the timestamp of the last Ulid acts as the
+/// physical part and the random bits as the logical counter. When a message carrying a Ulid is
+/// received, pass it to [`HlcGenerator::observe`]. Every Ulid generated afterwards is larger than
+/// it, even when the sender's clock runs ahead of the local one.
+///
+/// To keep a remote clock that is far off from dragging the local timestamps along, remote Ulids
+/// more than `max_skew` ahead of the local clock are rejected with [`ClockSkew`].
+///
+/// Observing a remote Ulid ahead of the local clock looks like a clock regression to the wrapped
+/// [`Generator`], so a regression threshold should be above `max_skew`.
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use ulid::{HlcGenerator, Ulid};
+///
+/// let mut generator = HlcGenerator::new(Duration::from_secs(1));
+///
+/// // a message from a service whose clock is 500ms ahead
+/// let remote = Ulid::from_datetime(std::time::SystemTime::now() + Duration::from_millis(500));
+/// generator.observe(remote).unwrap();
+///
+/// assert!(generator.generate().unwrap() > remote);
+/// ```
+#[derive(Debug, Clone)]
+pub struct HlcGenerator {
+    generator: Generator,
+    max_skew: Duration,
+}
+
+impl HlcGenerator {
+    /// Create a new HLC generator which accepts remote Ulids up to `max_skew` ahead of the local
+    /// clock
+    pub const fn new(max_skew: Duration) -> HlcGenerator {
+        HlcGenerator::from_generator(Generator::new(), max_skew)
+    }
+
+    /// Create a new HLC generator which continues from an existing generator
+    ///
+    /// The generator's clock, overflow policy and other settings are kept.
+    pub const fn from_generator(generator: Generator, max_skew: Duration) -> HlcGenerator {
+        HlcGenerator {
+            generator,
+            max_skew,
+        }
+    }
+
+    /// Merge a remote Ulid into the local state, so that every Ulid generated afterwards is
+    /// larger than it
+    ///
+    /// Fails without changing the local state if the remote Ulid is more than the maximum skew
+    /// ahead of the local clock.
+    pub fn observe(&mut self, remote: Ulid) -> Result<(), ClockSkew> {
+        let now = self
+            .generator
+            .now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO);
+        let skew = Duration::from_millis(remote.timestamp_ms()).saturating_sub(now);
+        if skew > self.max_skew {
+            return Err(ClockSkew { remote, skew });
+        }
+        self.generator.advance_past(remote);
+        Ok(())
+    }
+
+    /// Generate a new Ulid, larger than every Ulid generated or observed before. See
+    /// [`Generator::generate`].
+    pub fn generate(&mut self) -> Result<Ulid, Overflow<'_>> {
+        self.generator.generate()
+    }
+
+    /// Generate a new Ulid with the given source, larger than every Ulid generated or observed
+    /// before. See [`Generator::generate_with_source`].
+    pub fn generate_with_source<R>(&mut self, source: &mut R) -> Result<Ulid, Overflow<'_>>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        self.generator.generate_with_source(source)
+    }
+
+    /// Generate a new Ulid, larger than every Ulid generated or observed before, handling overflow
+    /// with the generator's [`crate::OverflowPolicy`]. See [`Generator::try_generate`].
+    pub fn try_generate(&mut self) -> Result<Ulid, GenerateError> {
+        self.generator.try_generate()
+    }
+
+    /// The largest Ulid generated or observed so far
+    pub fn last(&self) -> Ulid {
+        self.generator.previous()
+    }
+}
+
+/// A remote Ulid observed by [`HlcGenerator`] is too far ahead of the local clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClockSkew {
+    remote: Ulid,
+    skew: Duration,
+}
+
+impl ClockSkew {
+    /// The rejected remote Ulid
+    pub const fn remote(&self) -> Ulid {
+        self.remote
+    }
+
+    /// How far the remote Ulid is ahead of the local clock
+    pub const fn skew(&self) -> Duration {
+        self.skew
+    }
+}
+
+impl std::error::Error for ClockSkew {}
+
+impl fmt::Display for ClockSkew {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "remote Ulid is {}ms ahead of the local clock",
+            self.skew.as_millis()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_generator(now: SystemTime, max_skew: Duration) -> HlcGenerator {
+        let mut generator = Generator::new();
+        generator.set_clock(move || now);
+        HlcGenerator::from_generator(generator, max_skew)
+    }
+
+    #[test]
+    fn test_generate_after_observed() {
+        let now = SystemTime::now();
+        let mut generator = fixed_generator(now, Duration::from_secs(1));
+
+        let local = generator.generate().unwrap();
+        let remote = Ulid::from_datetime(now + Duration::from_millis(500));
+        generator.observe(remote).unwrap();
+        assert_eq!(generator.last(), remote);
+
+        let next = generator.generate().unwrap();
+        assert!(next > remote);
+        assert!(next > local);
+        assert_eq!(next.0, remote.0 + 1);
+    }
+
+    #[test]
+    fn test_observe_older_keeps_local() {
+        let now = SystemTime::now();
+        let mut generator = fixed_generator(now, Duration::from_secs(1));
+
+        let local = generator.generate().unwrap();
+        generator
+            .observe(Ulid::from_datetime(now - Duration::from_secs(10)))
+            .unwrap();
+        assert_eq!(generator.last(), local);
+        assert_eq!(generator.generate().unwrap().0, local.0 + 1);
+    }
+
+    #[test]
+    fn test_observe_rejects_skew() {
+        let now = SystemTime::now();
+        let mut generator = fixed_generator(now, Duration::from_secs(1));
+
+        let local = generator.generate().unwrap();
+        let remote = Ulid::from_datetime(now + Duration::from_secs(5));
+        let err = generator.observe(remote).unwrap_err();
+
+        assert_eq!(err.remote(), remote);
+        assert!(err.skew() > Duration::from_millis(4990));
+        assert_eq!(generator.last(), local);
+    }
+}
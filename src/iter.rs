@@ -0,0 +1,178 @@
+//! Iterator adapters for [`Generator`].
+
+use std::iter::FusedIterator;
+use std::time::SystemTime;
+
+use crate::{Generator, OverflowPolicy, Ulid};
+
+impl Generator {
+    /// Creates an endless iterator of monotonic Ulids
+    ///
+    /// Each Ulid is produced by [`Generator::generate_blocking`], so overflow is handled with the
+    /// generator's [`OverflowPolicy`] and [`OverflowPolicy::Error`] waits for the next millisecond.
+    ///
+    /// # Example
+    /// ```rust
+    /// use ulid::{Generator, OverflowPolicy};
+    ///
+    /// let mut generator = Generator::with_policy(OverflowPolicy::BorrowFuture);
+    /// let ulids: Vec<_> = generator.iter().take(1000).collect();
+    ///
+    /// assert!(ulids.windows(2).all(|pair| pair[0] < pair[1]));
+    /// ```
+    pub fn iter(&mut self) -> Iter<'_, impl rand::Rng> {
+        self.iter_with_source(crate::time_utils::rng())
+    }
+
+    /// Creates an endless iterator of monotonic Ulids using the given random number generator.
+    /// See [`Generator::iter`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use rand::prelude::*;
+    /// use ulid::Generator;
+    ///
+    /// let rng: StdRng = rand::make_rng();
+    /// let mut generator = Generator::new();
+    ///
+    /// for ulid in generator.iter_with_source(rng).take(10) {
+    ///     println!("{}", ulid);
+    /// }
+    /// ```
+    pub fn iter_with_source<R>(&mut self, source: R) -> Iter<'_, R>
+    where
+        R: rand::Rng,
+    {
+        Iter {
+            generator: self,
+            source,
+        }
+    }
+
+    /// Creates an iterator of monotonic Ulids, one for each time produced by `datetimes`
+    ///
+    /// Waiting for the clock makes no sense for given times, so on overflow the next millisecond
+    /// is borrowed as with [`OverflowPolicy::BorrowFuture`], unless the generator's policy is
+    /// [`OverflowPolicy::Random`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::{Duration, SystemTime};
+    /// use ulid::Generator;
+    ///
+    /// let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    /// let times = (0..100).map(|i| start + Duration::from_secs(i));
+    ///
+    /// let mut generator = Generator::new();
+    /// let fixtures: Vec<_> = generator.iter_from_datetime(times).collect();
+    ///
+    /// assert_eq!(fixtures.len(), 100);
+    /// assert_eq!(fixtures[0].datetime(), start);
+    /// ```
+    pub fn iter_from_datetime<I>(&mut self, datetimes: I) -> IterFromDatetime<'_, I::IntoIter>
+    where
+        I: IntoIterator<Item = SystemTime>,
+    {
+        IterFromDatetime {
+            generator: self,
+            datetimes: datetimes.into_iter(),
+        }
+    }
+}
+
+/// An endless iterator of monotonic Ulids created by [`Generator::iter`]
+#[derive(Debug)]
+pub struct Iter<'a, R> {
+    generator: &'a mut Generator,
+    source: R,
+}
+
+impl<R: rand::Rng> Iterator for Iter<'_, R> {
+    type Item = Ulid;
+
+    fn next(&mut self) -> Option<Ulid> {
+        Some(
+            self.generator
+                .generate_blocking_with_source(&mut self.source),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl<R: rand::Rng> FusedIterator for Iter<'_, R> {}
+
+/// An iterator of monotonic Ulids created by [`Generator::iter_from_datetime`]
+#[derive(Debug)]
+pub struct IterFromDatetime<'a, I> {
+    generator: &'a mut Generator,
+    datetimes: I,
+}
+
+impl<I: Iterator<Item = SystemTime>> Iterator for IterFromDatetime<'_, I> {
+    type Item = Ulid;
+
+    fn next(&mut self) -> Option<Ulid> {
+        let datetime = self.datetimes.next()?;
+        let policy = self.generator.policy();
+        Some(match self.generator.generate_from_datetime(datetime) {
+            Ok(ulid) => ulid,
+            Err(overflow) if policy == OverflowPolicy::Random => overflow.commit_overflow_random(),
+            Err(overflow) => overflow.commit_overflow_increment(),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.datetimes.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator<Item = SystemTime>> ExactSizeIterator for IterFromDatetime<'_, I> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_iter_with_source() {
+        let mut generator = Generator::with_policy(OverflowPolicy::BorrowFuture);
+        // every new millisecond starts at the maximum random value
+        let source = crate::StepRng::new(u64::MAX, 0);
+        let ulids: Vec<_> = generator.iter_with_source(source).take(100).collect();
+
+        assert_eq!(ulids.len(), 100);
+        assert!(ulids.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_iter_from_datetime() {
+        let dt = SystemTime::now();
+        let times = vec![dt; 10];
+        let mut generator = Generator::new();
+
+        let ulids: Vec<_> = generator.iter_from_datetime(times).collect();
+        assert_eq!(ulids.len(), 10);
+        assert!(ulids.windows(2).all(|pair| pair[0].0 + 1 == pair[1].0));
+    }
+
+    #[test]
+    fn test_iter_from_datetime_overflow() {
+        let dt = SystemTime::now();
+        let mut generator = Generator::new();
+        let mut source = crate::StepRng::new(u64::MAX, 0);
+        let maxed = generator
+            .generate_from_datetime_with_source(dt, &mut source)
+            .unwrap();
+
+        let next = generator.iter_from_datetime([dt]).next().unwrap();
+        assert_eq!(next.timestamp_ms(), maxed.timestamp_ms() + 1);
+        assert_eq!(next.random(), 0);
+
+        let later = dt + Duration::from_secs(1);
+        let ulids = generator.iter_from_datetime([dt, later]);
+        assert_eq!(ulids.len(), 2);
+    }
+}
@@ -0,0 +1,223 @@
+//! Independent monotonic sequences per key.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, SystemTime};
+
+use crate::{GenerateError, Generator, Overflow, Ulid};
+
+/// The number of keys below which idle keys are never evicted
+const MIN_SWEEP: usize = 16;
+
+/// A set of monotonic Ulid generators, one for each key
+///
+/// Ulids generated for the same key are strictly increasing, while different keys, such as tenants
+/// or event streams, do not share a counter. Overflow works exactly like [`Generator`] for each
+/// key.
+///
+/// Memory stays bounded by evicting keys that are idle: a key is dropped once its last Ulid is
+/// older than `max_idle`. A new generator for an evicted key only produces Ulids in newer
+/// milliseconds, so ordering is kept as long as the clock does not move backwards by more than
+/// `max_idle`. Idle keys are swept when the number of keys doubles, or with
+/// [`KeyedGenerator::evict_idle`]. The automatic sweep reads the time from the template's clock,
+/// the same one the generators use.
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use ulid::KeyedGenerator;
+///
+/// let mut generator = KeyedGenerator::new(Duration::from_secs(60));
+///
+/// let a1 = generator.generate("tenant-a").unwrap();
+/// generator.generate("tenant-b").unwrap();
+/// let a2 = generator.generate("tenant-a").unwrap();
+///
+/// assert!(a1 < a2);
+/// assert_eq!(generator.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyedGenerator<K> {
+    generators: HashMap<K, Generator>,
+    template: Generator,
+    max_idle: Duration,
+    sweep_at: usize,
+}
+
+impl<K: Hash + Eq> KeyedGenerator<K> {
+    /// Create a new keyed generator which evicts keys idle for longer than `max_idle`
+    pub fn new(max_idle: Duration) -> KeyedGenerator<K> {
+        KeyedGenerator::with_template(Generator::new(), max_idle)
+    }
+
+    /// Create a new keyed generator where every key starts as a clone of `template`
+    ///
+    /// This applies the template's [`crate::OverflowPolicy`], clock regression settings and
+    /// statistics to every key.
+    pub fn with_template(template: Generator, max_idle: Duration) -> KeyedGenerator<K> {
+        KeyedGenerator {
+            generators: HashMap::new(),
+            template,
+            max_idle,
+            sweep_at: MIN_SWEEP,
+        }
+    }
+
+    /// Gets the generator for `key`, creating it if needed
+    pub fn generator(&mut self, key: K) -> &mut Generator {
+        if self.generators.len() >= self.sweep_at && !self.generators.contains_key(&key) {
+            self.evict_idle(self.template.now());
+            self.sweep_at = (self.generators.len() * 2).max(MIN_SWEEP);
+        }
+        let template = &self.template;
+        self.generators
+            .entry(key)
+            .or_insert_with(|| template.clone())
+    }
+
+    /// Generate a new Ulid for `key`. Each call is guaranteed to provide a Ulid with a larger
+    /// value than the last call for the same key. See [`Generator::generate`].
+    pub fn generate(&mut self, key: K) -> Result<Ulid, Overflow<'_>> {
+        self.generator(key).generate()
+    }
+
+    /// Generate a new Ulid for `key` matching the given DateTime. See
+    /// [`Generator::generate_from_datetime`].
+    ///
+    /// Once a key has been evicted, its next Ulid is only compared against a fresh generator, so a
+    /// `datetime` older than the key's last Ulid produces a smaller Ulid for that key.
+    pub fn generate_from_datetime(
+        &mut self,
+        key: K,
+        datetime: SystemTime,
+    ) -> Result<Ulid, Overflow<'_>> {
+        self.generator(key).generate_from_datetime(datetime)
+    }
+
+    /// Generate a new Ulid for `key` with the given source. See
+    /// [`Generator::generate_with_source`].
+    pub fn generate_with_source<R>(&mut self, key: K, source: &mut R) -> Result<Ulid, Overflow<'_>>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        self.generator(key).generate_with_source(source)
+    }
+
+    /// Generate a new Ulid for `key` with the given source matching the given DateTime. See
+    /// [`Generator::generate_from_datetime_with_source`].
+    pub fn generate_from_datetime_with_source<R>(
+        &mut self,
+        key: K,
+        datetime: SystemTime,
+        source: &mut R,
+    ) -> Result<Ulid, Overflow<'_>>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        self.generator(key)
+            .generate_from_datetime_with_source(datetime, source)
+    }
+
+    /// Generate a new Ulid for `key`, handling overflow with the key's
+    /// [`crate::OverflowPolicy`]. See [`Generator::try_generate`].
+    pub fn try_generate(&mut self, key: K) -> Result<Ulid, GenerateError> {
+        self.generator(key).try_generate()
+    }
+
+    /// Drop every key whose last Ulid is older than `max_idle` at the given time
+    pub fn evict_idle(&mut self, now: SystemTime) {
+        let cutoff = now
+            .checked_sub(self.max_idle)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        self.generators
+            .retain(|_, generator| generator.previous().datetime() >= cutoff);
+    }
+
+    /// Drop the state of `key`
+    pub fn remove(&mut self, key: &K) -> Option<Generator> {
+        self.generators.remove(key)
+    }
+
+    /// The number of keys currently tracked
+    pub fn len(&self) -> usize {
+        self.generators.len()
+    }
+
+    /// Returns true if no keys are tracked
+    pub fn is_empty(&self) -> bool {
+        self.generators.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_are_independent() {
+        let dt = SystemTime::now();
+        let mut source = crate::StepRng::new(u64::MAX, 0);
+        let mut generator = KeyedGenerator::new(Duration::from_secs(60));
+
+        // "a" is about to overflow, which must not affect "b"
+        let a1 = generator
+            .generate_from_datetime_with_source("a", dt, &mut source)
+            .unwrap();
+        assert_eq!(a1.random(), crate::bitmask!(Ulid::RAND_BITS));
+        let b1 = generator
+            .generate_from_datetime_with_source("b", dt, &mut source)
+            .unwrap();
+        assert_eq!(a1, b1);
+
+        let b2 = generator.generate_from_datetime("b", dt).unwrap_err();
+        let b2 = b2.commit_overflow_increment();
+        assert!(b1 < b2);
+
+        let a2 = generator
+            .generate_from_datetime("a", dt)
+            .unwrap_err()
+            .commit_overflow_increment();
+        assert_eq!(a2, b2);
+    }
+
+    #[test]
+    fn test_evict_idle() {
+        let dt = SystemTime::now();
+        let mut generator = KeyedGenerator::new(Duration::from_secs(60));
+
+        generator
+            .generate_from_datetime(1, dt - Duration::from_secs(120))
+            .unwrap();
+        generator.generate_from_datetime(2, dt).unwrap();
+        generator.evict_idle(dt);
+
+        assert_eq!(generator.len(), 1);
+        assert!(generator.remove(&2).is_some());
+        assert!(generator.is_empty());
+    }
+
+    #[test]
+    fn test_sweep_uses_template_clock() {
+        let frozen = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut template = Generator::new();
+        template.set_clock(move || frozen);
+        let mut generator = KeyedGenerator::with_template(template, Duration::from_secs(60));
+
+        // idle according to the system clock, but not according to the template's clock
+        for key in 0..MIN_SWEEP * 4 {
+            generator.generate(key).unwrap();
+        }
+        assert_eq!(generator.len(), MIN_SWEEP * 4);
+    }
+
+    #[test]
+    fn test_memory_is_bounded() {
+        let old = crate::time_utils::now() - Duration::from_secs(120);
+        let mut generator = KeyedGenerator::new(Duration::from_secs(60));
+
+        for key in 0..10_000 {
+            generator.generate_from_datetime(key, old).unwrap();
+        }
+        assert!(generator.len() <= MIN_SWEEP);
+    }
+}
@@ -35,15 +35,69 @@
 #[cfg(all(doctest, feature = "std"))]
 struct ReadMeDoctest;
 
+#[cfg(feature = "async")]
+mod async_generator;
+#[cfg(feature = "std")]
+mod backfill;
 mod base32;
+#[cfg(feature = "rayon")]
+pub mod bulk;
+#[cfg(feature = "std")]
+mod clock;
+#[cfg(any(
+    feature = "diesel-postgres",
+    feature = "diesel-mysql",
+    feature = "diesel-sqlite"
+))]
+mod diesel_types;
 #[cfg(feature = "std")]
 mod generator;
+#[cfg(feature = "std")]
+mod global;
+mod guid;
+#[cfg(feature = "std")]
+mod hlc;
+#[cfg(feature = "std")]
+mod iter;
+#[cfg(feature = "std")]
+mod keyed;
+#[cfg(feature = "mysql")]
+mod mysql;
 #[cfg(feature = "postgres")]
 mod postgres;
 #[cfg(feature = "rkyv")]
 mod rkyv;
+#[cfg(feature = "rusqlite")]
+mod rusqlite;
+#[cfg(feature = "std")]
+mod seeded;
 #[cfg(feature = "serde")]
 pub mod serde;
+#[cfg(feature = "shared")]
+mod shared;
+#[cfg(feature = "std")]
+mod splitmix;
+#[cfg(any(
+    feature = "sqlx-postgres",
+    feature = "sqlx-mysql",
+    feature = "sqlx-sqlite",
+    feature = "diesel-postgres",
+    feature = "diesel-mysql",
+    feature = "diesel-sqlite",
+    feature = "rusqlite",
+    feature = "mysql"
+))]
+pub mod sql;
+#[cfg(any(
+    feature = "sqlx-postgres",
+    feature = "sqlx-mysql",
+    feature = "sqlx-sqlite"
+))]
+mod sqlx;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(all(feature = "testing", feature = "std"))]
+pub mod testing;
 #[cfg(feature = "std")]
 mod time;
 #[cfg(feature = "std")]
@@ -60,9 +114,30 @@ use core::convert::TryFrom;
 use core::fmt;
 use core::str::FromStr;
 
+#[cfg(feature = "async")]
+pub use crate::async_generator::{AsyncGenerator, Timer, UlidStream, YieldNow, YieldTimer};
+#[cfg(feature = "std")]
+pub use crate::backfill::BackfillGenerator;
 pub use crate::base32::{DecodeError, EncodeError, ULID_LEN};
 #[cfg(feature = "std")]
-pub use crate::generator::{Generator, Overflow};
+pub use crate::clock::{Clock, MonotonicClock, SystemClock};
+#[cfg(feature = "std")]
+pub use crate::generator::{
+    ClockRegression, ClockRegressionPolicy, GenerateError, Generator, Overflow, OverflowError,
+    OverflowPolicy,
+};
+#[cfg(feature = "std")]
+pub use crate::hlc::{ClockSkew, HlcGenerator};
+#[cfg(feature = "std")]
+pub use crate::iter::{Iter, IterFromDatetime};
+#[cfg(feature = "std")]
+pub use crate::keyed::KeyedGenerator;
+#[cfg(feature = "std")]
+pub use crate::seeded::SeededGenerator;
+#[cfg(feature = "shared")]
+pub use crate::shared::{SharedError, SharedGenerator};
+#[cfg(feature = "std")]
+pub use crate::stats::GeneratorStats;
 
 /// Create a right-aligned bitmask of $len bits
 macro_rules! bitmask {
@@ -86,6 +161,23 @@ pub(crate) use bitmask;
     feature = "rkyv",
     derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize)
 )]
+#[cfg_attr(
+    any(
+        feature = "diesel-postgres",
+        feature = "diesel-mysql",
+        feature = "diesel-sqlite"
+    ),
+    derive(
+        ::diesel::expression::AsExpression,
+        ::diesel::deserialize::FromSqlRow
+    ),
+    diesel(sql_type = ::diesel::sql_types::Binary),
+    diesel(sql_type = ::diesel::sql_types::Text)
+)]
+#[cfg_attr(
+    feature = "diesel-postgres",
+    diesel(sql_type = ::diesel::sql_types::Uuid)
+)]
 pub struct Ulid(pub u128);
 
 impl Ulid {
@@ -0,0 +1,122 @@
+use std::convert::TryFrom;
+
+use mysql_common::value::convert::{FromValue, FromValueError};
+use mysql_common::value::Value;
+
+use crate::sql::{ulid_from_slice, UlidBytes, UlidText};
+use crate::Ulid;
+
+/// Ulids are stored as 16 big-endian bytes, so the index order of a `binary(16)` column matches
+/// the order of the Ulids.
+impl From<Ulid> for Value {
+    fn from(ulid: Ulid) -> Value {
+        Value::Bytes(ulid.to_bytes().to_vec())
+    }
+}
+
+impl From<UlidBytes> for Value {
+    fn from(bytes: UlidBytes) -> Value {
+        bytes.0.into()
+    }
+}
+
+impl From<UlidText> for Value {
+    fn from(text: UlidText) -> Value {
+        Value::Bytes(text.0.to_string().into_bytes())
+    }
+}
+
+/// A Ulid decoded from 16 bytes or a `char(26)` value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParsedUlid(Ulid);
+
+impl TryFrom<Value> for ParsedUlid {
+    type Error = FromValueError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Bytes(ref bytes) => match ulid_from_slice(bytes) {
+                Ok(ulid) => Ok(ParsedUlid(ulid)),
+                Err(_) => Err(FromValueError(value)),
+            },
+            value => Err(FromValueError(value)),
+        }
+    }
+}
+
+impl From<ParsedUlid> for Ulid {
+    fn from(parsed: ParsedUlid) -> Ulid {
+        parsed.0
+    }
+}
+
+impl From<ParsedUlid> for UlidBytes {
+    fn from(parsed: ParsedUlid) -> UlidBytes {
+        UlidBytes(parsed.0)
+    }
+}
+
+impl From<ParsedUlid> for UlidText {
+    fn from(parsed: ParsedUlid) -> UlidText {
+        UlidText(parsed.0)
+    }
+}
+
+impl FromValue for Ulid {
+    type Intermediate = ParsedUlid;
+}
+
+impl FromValue for UlidBytes {
+    type Intermediate = ParsedUlid;
+}
+
+impl FromValue for UlidText {
+    type Intermediate = ParsedUlid;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::fixture::{ulid, TEXT};
+    use mysql_common::value::convert::{from_value, from_value_opt, ToValue};
+
+    #[test]
+    fn test_to_value() {
+        assert_eq!(ulid().to_value(), Value::Bytes(ulid().to_bytes().to_vec()));
+        assert_eq!(UlidBytes(ulid()).to_value(), ulid().to_value());
+        assert_eq!(
+            UlidText(ulid()).to_value(),
+            Value::Bytes(TEXT.as_bytes().to_vec())
+        );
+        assert_eq!(Some(ulid()).to_value(), ulid().to_value());
+        assert_eq!(None::<Ulid>.to_value(), Value::NULL);
+    }
+
+    #[test]
+    fn test_from_value() {
+        let bytes = Value::Bytes(ulid().to_bytes().to_vec());
+        let text = Value::Bytes(TEXT.as_bytes().to_vec());
+        let lowercase = Value::Bytes(TEXT.to_lowercase().into_bytes());
+        for value in &[bytes, text, lowercase] {
+            assert_eq!(from_value::<Ulid>(value.clone()), ulid());
+            assert_eq!(from_value::<UlidBytes>(value.clone()), UlidBytes(ulid()));
+            assert_eq!(from_value::<UlidText>(value.clone()), UlidText(ulid()));
+        }
+
+        assert_eq!(from_value::<Option<Ulid>>(Value::NULL), None);
+    }
+
+    #[test]
+    fn test_from_invalid_value() {
+        let values = [
+            Value::Bytes(vec![1, 2]),
+            Value::Bytes(TEXT.replace('Z', "U").into_bytes()),
+            Value::Int(42),
+            Value::NULL,
+        ];
+        for value in &values {
+            let FromValueError(rejected) = from_value_opt::<Ulid>(value.clone()).unwrap_err();
+            assert_eq!(&rejected, value);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::Ulid;
+use crate::{Ulid, ULID_LEN};
 use bytes::BufMut;
 use bytes::BytesMut;
 use postgres_types::accepts;
@@ -6,25 +6,56 @@ use postgres_types::to_sql_checked;
 use postgres_types::{FromSql, IsNull, ToSql, Type};
 use std::error::Error;
 
+/// Ulids are stored as 16 bytes in `uuid` and `bytea` columns, and as their canonical string in
+/// text columns. `Option<Ulid>` and arrays of Ulids use the impls of `postgres-types` for these.
 impl FromSql<'_> for Ulid {
-    fn from_sql(_ty: &Type, raw: &[u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
-        if raw.len() != 16 {
-            return Err("invalid message length: uuid size mismatch".into());
+    fn from_sql(ty: &Type, raw: &[u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        if *ty == Type::UUID || *ty == Type::BYTEA {
+            if raw.len() != 16 {
+                return Err(format!(
+                    "invalid message length: expected 16 bytes for a ulid, got {}",
+                    raw.len()
+                )
+                .into());
+            }
+            let mut bytes = [0; 16];
+            bytes.copy_from_slice(raw);
+            return Ok(Ulid(u128::from_be_bytes(bytes)));
         }
-        let mut bytes = [0; 16];
-        bytes.copy_from_slice(raw);
-        Ok(Ulid(u128::from_be_bytes(bytes)))
+
+        let text = std::str::from_utf8(raw)?;
+        // char(n) columns wider than a ulid are padded with spaces
+        let text = if *ty == Type::BPCHAR {
+            text.trim_end_matches(' ')
+        } else {
+            text
+        };
+        if text.len() != ULID_LEN {
+            return Err(format!(
+                "invalid message length: expected {} characters for a ulid, got {}",
+                ULID_LEN,
+                text.len()
+            )
+            .into());
+        }
+        Ok(Ulid::from_string(text)?)
     }
-    accepts!(UUID);
+
+    accepts!(UUID, BYTEA, TEXT, VARCHAR, BPCHAR);
 }
 
 impl ToSql for Ulid {
-    fn to_sql(&self, _: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
-        w.put_slice(&self.0.to_be_bytes());
+    fn to_sql(&self, ty: &Type, w: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        if *ty == Type::UUID || *ty == Type::BYTEA {
+            w.put_slice(&self.0.to_be_bytes());
+        } else {
+            let mut buffer = [0; ULID_LEN];
+            w.put_slice(self.array_to_str(&mut buffer).as_bytes());
+        }
         Ok(IsNull::No)
     }
 
-    accepts!(UUID);
+    accepts!(UUID, BYTEA, TEXT, VARCHAR, BPCHAR);
     to_sql_checked!();
 }
 
@@ -35,6 +66,12 @@ mod tests {
     use postgres_types::{FromSql, Type};
     use std::io::Read;
 
+    fn to_sql<T: ToSql>(value: &T, ty: &Type) -> Vec<u8> {
+        let mut w = BytesMut::new();
+        value.to_sql_checked(ty, &mut w).unwrap();
+        w.to_vec()
+    }
+
     #[test]
     fn postgres_cycle() {
         let ulid = Ulid::from_string("3Q38XWW0Q98GMAD3NHWZM2PZWZ").unwrap();
@@ -49,4 +86,77 @@ mod tests {
 
         assert_eq!(ulid, Ulid::from_sql(t, &bs).unwrap());
     }
+
+    #[test]
+    fn postgres_binary_types() {
+        let wire = [
+            0x01, 0x68, 0xd3, 0xff, 0x00, 0xcf, 0x86, 0x59, 0xad, 0xd4, 0x9a, 0x16, 0xd3, 0x69,
+            0xc5, 0xff,
+        ];
+        let ulid = Ulid::from_bytes(wire);
+        for ty in &[Type::UUID, Type::BYTEA] {
+            assert_eq!(to_sql(&ulid, ty), wire);
+            assert_eq!(Ulid::from_sql(ty, &wire).unwrap(), ulid);
+
+            let err = Ulid::from_sql(ty, &wire[..15]).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "invalid message length: expected 16 bytes for a ulid, got 15"
+            );
+        }
+    }
+
+    #[test]
+    fn postgres_text_types() {
+        let text = "01D39ZY06FGSCTVN4T2V9PKHFZ";
+        let ulid = Ulid::from_string(text).unwrap();
+        for ty in &[Type::TEXT, Type::VARCHAR, Type::BPCHAR] {
+            assert_eq!(to_sql(&ulid, ty), text.as_bytes());
+            assert_eq!(Ulid::from_sql(ty, text.as_bytes()).unwrap(), ulid);
+            let lowercase = text.to_lowercase();
+            assert_eq!(Ulid::from_sql(ty, lowercase.as_bytes()).unwrap(), ulid);
+        }
+
+        // a char(32) column
+        let padded = format!("{:32}", text);
+        assert_eq!(
+            Ulid::from_sql(&Type::BPCHAR, padded.as_bytes()).unwrap(),
+            ulid
+        );
+        let err = Ulid::from_sql(&Type::TEXT, padded.as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid message length: expected 26 characters for a ulid, got 32"
+        );
+
+        let invalid = text.replace('Z', "U");
+        assert!(Ulid::from_sql(&Type::TEXT, invalid.as_bytes()).is_err());
+        assert!(!<Ulid as FromSql>::accepts(&Type::INT8));
+    }
+
+    #[test]
+    fn postgres_option_and_array() {
+        let ulid = Ulid::from_string("3Q38XWW0Q98GMAD3NHWZM2PZWZ").unwrap();
+        assert!(<Option<Ulid> as FromSql>::from_sql_null(&Type::UUID)
+            .unwrap()
+            .is_none());
+        assert!(<Option<Ulid> as FromSql>::accepts(&Type::BPCHAR));
+
+        // uuid[] holding a ulid and a null
+        let mut wire = vec![
+            0, 0, 0, 1, // dimensions
+            0, 0, 0, 1, // has nulls
+            0, 0, 0x0b, 0x86, // element type, uuid
+            0, 0, 0, 2, // length
+            0, 0, 0, 1, // lower bound
+            0, 0, 0, 16, // element length
+        ];
+        wire.extend_from_slice(&ulid.to_bytes());
+        wire.extend_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+
+        let values = Vec::<Option<Ulid>>::from_sql(&Type::UUID_ARRAY, &wire).unwrap();
+        assert_eq!(values, [Some(ulid), None]);
+        assert_eq!(to_sql(&values, &Type::UUID_ARRAY), wire);
+        assert!(<Vec<Ulid> as FromSql>::accepts(&Type::TEXT_ARRAY));
+    }
 }
@@ -0,0 +1,163 @@
+use std::convert::TryFrom;
+
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+
+use crate::sql::{ulid_from_text, UlidBytes, UlidText};
+use crate::Ulid;
+
+/// Ulids are stored as 16 big-endian bytes, which sort in the same order as the Ulids, and are
+/// decoded from a 16-byte `BLOB` or a `TEXT` value.
+impl ToSql for Ulid {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_bytes().to_vec()))
+    }
+}
+
+impl FromSql for Ulid {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Blob(bytes) => {
+                <[u8; 16]>::try_from(bytes)
+                    .map(Ulid::from_bytes)
+                    .map_err(|_| FromSqlError::InvalidBlobSize {
+                        expected_size: 16,
+                        blob_size: bytes.len(),
+                    })
+            }
+            ValueRef::Text(_) => ulid_from_text(value.as_str()?).map_err(FromSqlError::Other),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+impl ToSql for UlidText {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.0.to_string()))
+    }
+}
+
+impl FromSql for UlidText {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        Ulid::column_result(value).map(UlidText)
+    }
+}
+
+impl ToSql for UlidBytes {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        self.0.to_sql()
+    }
+}
+
+impl FromSql for UlidBytes {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        Ulid::column_result(value).map(UlidBytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::fixture::{ulid, TEXT};
+    use rusqlite::types::Type;
+    use rusqlite::{params, Connection};
+
+    fn connection() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE events (id BLOB PRIMARY KEY, name TEXT)")
+            .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_blob_and_text_storage() {
+        let conn = connection();
+        conn.execute(
+            "INSERT INTO events VALUES (?1, ?2)",
+            params![ulid(), UlidText(ulid())],
+        )
+        .unwrap();
+
+        let (id, name): (Ulid, UlidText) = conn
+            .query_row("SELECT id, name FROM events", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(id, ulid());
+        assert_eq!(name, UlidText(ulid()));
+
+        let (kind, length, text): (String, i64, String) = conn
+            .query_row(
+                "SELECT typeof(id), length(id), name FROM events",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(kind, "blob");
+        assert_eq!(length, 16);
+        assert_eq!(text, TEXT);
+
+        // each form decodes from the other
+        let bytes: UlidBytes = conn
+            .query_row("SELECT name FROM events", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(bytes, UlidBytes(ulid()));
+        let text: UlidText = conn
+            .query_row("SELECT id FROM events", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(text, UlidText(ulid()));
+    }
+
+    #[test]
+    fn test_blob_order() {
+        let conn = connection();
+        let ulids = [
+            Ulid::from_parts(2, 0),
+            Ulid::from_parts(1, u128::MAX),
+            Ulid::nil(),
+        ];
+        for ulid in &ulids {
+            conn.execute("INSERT INTO events (id) VALUES (?1)", [ulid])
+                .unwrap();
+        }
+
+        let mut statement = conn.prepare("SELECT id FROM events ORDER BY id").unwrap();
+        let sorted: Vec<Ulid> = statement
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let mut expected = ulids.to_vec();
+        expected.sort();
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        let conn = Connection::open_in_memory().unwrap();
+        let get = |sql: &str| conn.query_row(sql, [], |row| row.get::<_, Ulid>(0));
+
+        match get("SELECT x'0102'").unwrap_err() {
+            rusqlite::Error::FromSqlConversionFailure(_, Type::Blob, err) => {
+                assert_eq!(
+                    err.to_string(),
+                    "Cannot read 16 byte value out of 2 byte blob"
+                );
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+
+        let err = get("SELECT 'not a ulid'").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("expected 16 bytes or 26 characters for a ulid, got 10"));
+
+        assert!(matches!(
+            get("SELECT 42").unwrap_err(),
+            rusqlite::Error::InvalidColumnType(_, _, Type::Integer)
+        ));
+        assert!(conn
+            .query_row("SELECT NULL", [], |row| row.get::<_, Option<Ulid>>(0))
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,148 @@
+//! Reproducible generation for tests and golden files.
+
+use std::time::{Duration, SystemTime};
+
+use crate::splitmix::SplitMix64;
+use crate::{Generator, Ulid};
+
+/// A monotonic Ulid generator whose output only depends on its seed and start time
+///
+/// Each call uses a fixed point in time, starting at `start` and advancing by the step after every
+/// call, and draws its random bits from a seeded generator with a fixed algorithm. The same seed,
+/// start and step therefore produce the same realistic-looking Ulids on every platform, which keeps
+/// snapshot tests and golden files stable.
+///
+/// The default step is one millisecond. With a smaller step several Ulids share a millisecond and
+/// are incremented as with [`Generator`]. On overflow the next millisecond is borrowed, so
+/// generation never fails.
+///
+/// The random bits are predictable, so these Ulids must not be used outside of tests.
+///
+/// # Example
+/// ```rust
+/// use std::time::{Duration, SystemTime};
+/// use ulid::SeededGenerator;
+///
+/// let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// let mut generator = SeededGenerator::new(42, start);
+///
+/// let ulid1 = generator.generate();
+/// let ulid2 = generator.generate();
+///
+/// assert_eq!(ulid1.datetime(), start);
+/// assert_eq!(ulid2.datetime(), start + Duration::from_millis(1));
+/// assert_eq!(ulid1, SeededGenerator::new(42, start).generate());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SeededGenerator {
+    generator: Generator,
+    source: SplitMix64,
+    next: SystemTime,
+    step: Duration,
+}
+
+impl SeededGenerator {
+    /// Create a generator for the given seed whose first Ulid has the `start` timestamp
+    pub const fn new(seed: u64, start: SystemTime) -> SeededGenerator {
+        SeededGenerator {
+            generator: Generator::new(),
+            source: SplitMix64::new(seed),
+            next: start,
+            step: Duration::from_millis(1),
+        }
+    }
+
+    /// Sets how far the time advances after each generated Ulid
+    pub const fn with_step(mut self, step: Duration) -> SeededGenerator {
+        self.step = step;
+        self
+    }
+
+    /// Gets the time advance after each generated Ulid
+    pub const fn step(&self) -> Duration {
+        self.step
+    }
+
+    /// The time used for the next generated Ulid
+    pub const fn next_datetime(&self) -> SystemTime {
+        self.next
+    }
+
+    /// Generate the next Ulid. Each call is guaranteed to provide a Ulid with a larger value than
+    /// the last call.
+    pub fn generate(&mut self) -> Ulid {
+        let ulid = match self
+            .generator
+            .generate_from_datetime_with_source(self.next, &mut self.source)
+        {
+            Ok(ulid) => ulid,
+            Err(overflow) => overflow.commit_overflow_increment(),
+        };
+        self.next += self.step;
+        ulid
+    }
+}
+
+impl Iterator for SeededGenerator {
+    type Item = Ulid;
+
+    fn next(&mut self) -> Option<Ulid> {
+        Some(self.generate())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+    }
+
+    #[test]
+    fn test_golden() {
+        let ulids: Vec<_> = SeededGenerator::new(1234567, start())
+            .take(3)
+            .map(|ulid| ulid.to_string())
+            .collect();
+        assert_eq!(
+            ulids,
+            [
+                "01HF7YAT00T0BJRWZGGHC583X5",
+                "01HF7YAT01QKJKZFQQ83MHEYSZ",
+                "01HF7YAT026HKPRKVXQJC9JH7P",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_step() {
+        let step = Duration::from_secs(1);
+        let mut generator = SeededGenerator::new(7, start()).with_step(step);
+        let ulids: Vec<_> = generator.by_ref().take(10).collect();
+
+        assert!(ulids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(ulids[9].datetime(), start() + step * 9);
+        assert_eq!(generator.next_datetime(), start() + step * 10);
+    }
+
+    #[test]
+    fn test_zero_step() {
+        let generator = SeededGenerator::new(7, start()).with_step(Duration::ZERO);
+        let ulids: Vec<_> = generator.take(10).collect();
+
+        assert!(ulids.windows(2).all(|pair| pair[0].0 + 1 == pair[1].0));
+        assert!(ulids.iter().all(|ulid| ulid.datetime() == start()));
+    }
+
+    #[test]
+    fn test_seeds_differ() {
+        let mut generator1 = SeededGenerator::new(1, start());
+        let mut generator2 = SeededGenerator::new(2, start());
+        assert_ne!(generator1.generate(), generator2.generate());
+    }
+}
@@ -1,12 +1,20 @@
 //! Serialization and deserialization.
 //!
 //! By default, serialization and deserialization go through ULID's 26-character
-//! canonical string representation as set by the ULID standard.
+//! canonical string representation as set by the ULID standard. Formats which are not
+//! human readable, such as bincode or MessagePack, use the 16 bytes of
+//! [`Ulid::to_bytes`] instead. Deserialization accepts either form, as well as a u128
+//! or a sequence of 16 bytes, without allocating.
 //!
 //! ULIDs can optionally be serialized as u128 integers using the `ulid_as_u128`
-//! module. See the module's documentation for examples.
+//! module. See the module's documentation for examples. The other `ulid_as_*` modules
+//! provide more representations, each with `option` and `seq` submodules for
+//! `Option<Ulid>` and `Vec<Ulid>` fields.
 
 use crate::{Ulid, ULID_LEN};
+use core::convert::TryFrom;
+use core::fmt;
+use serde::de::{self, SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 impl Serialize for Ulid {
@@ -14,9 +22,13 @@ impl Serialize for Ulid {
     where
         S: Serializer,
     {
-        let mut buffer = [0; ULID_LEN];
-        let text = self.array_to_str(&mut buffer);
-        text.serialize(serializer)
+        if serializer.is_human_readable() {
+            let mut buffer = [0; ULID_LEN];
+            let text = self.array_to_str(&mut buffer);
+            text.serialize(serializer)
+        } else {
+            serializer.serialize_bytes(&self.to_bytes())
+        }
     }
 }
 
@@ -25,11 +37,134 @@ impl<'de> Deserialize<'de> for Ulid {
     where
         D: Deserializer<'de>,
     {
-        let deserialized_str = String::deserialize(deserializer)?;
-        Self::from_string(&deserialized_str).map_err(serde::de::Error::custom)
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(UlidVisitor)
+        } else {
+            deserializer.deserialize_bytes(UlidVisitor)
+        }
     }
 }
 
+/// Accepts every representation of a Ulid without allocating: the canonical string, 16 bytes,
+/// the string as bytes, a u128 and a sequence of 16 bytes
+struct UlidVisitor;
+
+impl<'de> Visitor<'de> for UlidVisitor {
+    type Value = Ulid;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a ULID string, 16 bytes or a u128")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Ulid, E> {
+        Ulid::from_string(value).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Ulid, E> {
+        if let Ok(bytes) = <[u8; 16]>::try_from(value) {
+            return Ok(Ulid::from_bytes(bytes));
+        }
+        match core::str::from_utf8(value) {
+            Ok(text) if text.len() == ULID_LEN => self.visit_str(text),
+            _ => Err(E::invalid_length(value.len(), &self)),
+        }
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<Ulid, E> {
+        Ok(Ulid(value))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Ulid, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut bytes = [0; 16];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(17, &self));
+        }
+        Ok(Ulid::from_bytes(bytes))
+    }
+}
+
+/// Adds `option` and `seq` submodules to an adapter module, for `Option<Ulid>` and `Vec<Ulid>`
+/// fields, which use the `serialize` and `deserialize` functions of the adapter for each Ulid
+macro_rules! option_and_seq {
+    () => {
+        struct As<'a>(&'a crate::Ulid);
+
+        impl serde::Serialize for As<'_> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serialize(self.0, serializer)
+            }
+        }
+
+        struct De(crate::Ulid);
+
+        impl<'de> serde::Deserialize<'de> for De {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                deserialize(deserializer).map(De)
+            }
+        }
+
+        /// The same representation for `Option<Ulid>` fields.
+        pub mod option {
+            use crate::Ulid;
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+            /// Serializes an optional ULID.
+            pub fn serialize<S>(value: &Option<Ulid>, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                value.as_ref().map(super::As).serialize(serializer)
+            }
+
+            /// Deserializes an optional ULID.
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Ulid>, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                Ok(Option::<super::De>::deserialize(deserializer)?.map(|de| de.0))
+            }
+        }
+
+        /// The same representation for `Vec<Ulid>` fields.
+        #[cfg(feature = "std")]
+        pub mod seq {
+            use crate::Ulid;
+            use serde::{Deserialize, Deserializer, Serializer};
+
+            /// Serializes a sequence of ULIDs.
+            pub fn serialize<S>(value: &[Ulid], serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.collect_seq(value.iter().map(super::As))
+            }
+
+            /// Deserializes a sequence of ULIDs.
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<Ulid>, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let values = Vec::<super::De>::deserialize(deserializer)?;
+                Ok(values.into_iter().map(|de| de.0).collect())
+            }
+        }
+    };
+}
+
 /// Serialization and deserialization of ULIDs through their inner u128 type.
 ///
 /// To use it, annotate a field with
@@ -37,6 +172,8 @@ impl<'de> Deserialize<'de> for Ulid {
 /// `#[serde(serialize_with = "ulid_as_u128")]`, or
 /// `#[serde(deserialize_with = "ulid_as_u128")]`.
 ///
+/// `Option<Ulid>` and `Vec<Ulid>` fields use `ulid_as_u128::option` and `ulid_as_u128::seq`.
+///
 /// # Examples
 /// ```
 /// # use ulid::Ulid;
@@ -68,6 +205,8 @@ pub mod ulid_as_u128 {
         let deserialized_u128 = u128::deserialize(deserializer)?;
         Ok(Ulid(deserialized_u128))
     }
+
+    option_and_seq!();
 }
 
 /// Serialization and deserialization of ULIDs through UUID strings.
@@ -77,6 +216,8 @@ pub mod ulid_as_u128 {
 /// `#[serde(serialize_with = "ulid_as_uuid")]`, or
 /// `#[serde(deserialize_with = "ulid_as_uuid")]`.
 ///
+/// `Option<Ulid>` and `Vec<Ulid>` fields use `ulid_as_uuid::option` and `ulid_as_uuid::seq`.
+///
 /// # Examples
 /// ```
 /// # use ulid::Ulid;
@@ -112,4 +253,615 @@ pub mod ulid_as_uuid {
         let de_uuid = Uuid::parse_str(&de_string).map_err(serde::de::Error::custom)?;
         Ok(Ulid::from(de_uuid))
     }
+
+    option_and_seq!();
+}
+
+/// Serialization and deserialization of ULIDs as 16 bytes.
+///
+/// The bytes are big-endian, as returned by [`Ulid::to_bytes`](crate::Ulid::to_bytes), which is
+/// also the byte representation of the UUID with the same value. Formats without a byte type,
+/// such as JSON, write an array of numbers.
+///
+/// `Option<Ulid>` and `Vec<Ulid>` fields use `ulid_as_bytes::option` and `ulid_as_bytes::seq`.
+///
+/// # Examples
+/// ```
+/// # use ulid::Ulid;
+/// # use ulid::serde::ulid_as_bytes;
+/// # use serde_derive::{Serialize, Deserialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct BytesExample {
+///     #[serde(with = "ulid_as_bytes")]
+///     identifier: Ulid,
+///     #[serde(with = "ulid_as_bytes::option")]
+///     parent: Option<Ulid>,
+/// }
+/// ```
+pub mod ulid_as_bytes {
+    use super::UlidVisitor;
+    use crate::Ulid;
+    use serde::{Deserializer, Serializer};
+
+    /// Serializes a ULID as 16 bytes.
+    pub fn serialize<S>(value: &Ulid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&value.to_bytes())
+    }
+
+    /// Deserializes a ULID from 16 bytes.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Ulid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(UlidVisitor)
+    }
+
+    option_and_seq!();
+}
+
+/// Serialization and deserialization of ULIDs as 32 lowercase hexadecimal digits.
+///
+/// Deserialization also accepts uppercase digits.
+///
+/// `Option<Ulid>` and `Vec<Ulid>` fields use `ulid_as_hex::option` and `ulid_as_hex::seq`.
+///
+/// # Examples
+/// ```
+/// # use ulid::Ulid;
+/// # use ulid::serde::ulid_as_hex;
+/// # use serde_derive::{Serialize, Deserialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct HexExample {
+///     #[serde(with = "ulid_as_hex")]
+///     identifier: Ulid
+/// }
+/// ```
+pub mod ulid_as_hex {
+    use crate::Ulid;
+    use core::fmt;
+    use serde::de::{self, Visitor};
+    use serde::{Deserializer, Serializer};
+
+    const HEX_LEN: usize = 32;
+
+    /// Serializes a ULID as a hexadecimal string.
+    pub fn serialize<S>(value: &Ulid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+        let mut buffer = [0; HEX_LEN];
+        for (i, digit) in buffer.iter_mut().enumerate() {
+            let nibble = (value.0 >> (4 * (HEX_LEN - 1 - i))) & 0xf;
+            *digit = DIGITS[nibble as usize];
+        }
+        // the buffer only contains ASCII digits
+        serializer.serialize_str(core::str::from_utf8(&buffer).unwrap())
+    }
+
+    /// Deserializes a ULID from a hexadecimal string.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Ulid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(HexVisitor)
+    }
+
+    struct HexVisitor;
+
+    impl Visitor<'_> for HexVisitor {
+        type Value = Ulid;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("32 hexadecimal digits")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Ulid, E> {
+            if value.len() != HEX_LEN || !value.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+                return Err(E::invalid_value(de::Unexpected::Str(value), &self));
+            }
+            u128::from_str_radix(value, 16)
+                .map(Ulid)
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+        }
+    }
+
+    option_and_seq!();
+}
+
+/// Serialization and deserialization of ULIDs through their lowercase string representation.
+///
+/// Deserialization accepts either case.
+///
+/// `Option<Ulid>` and `Vec<Ulid>` fields use `ulid_as_lowercase::option` and
+/// `ulid_as_lowercase::seq`.
+///
+/// # Examples
+/// ```
+/// # use ulid::Ulid;
+/// # use ulid::serde::ulid_as_lowercase;
+/// # use serde_derive::{Serialize, Deserialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct LowercaseExample {
+///     #[serde(with = "ulid_as_lowercase::seq")]
+///     identifiers: Vec<Ulid>
+/// }
+/// ```
+pub mod ulid_as_lowercase {
+    use super::UlidVisitor;
+    use crate::{Ulid, ULID_LEN};
+    use serde::{Deserializer, Serializer};
+
+    /// Serializes a ULID as a lowercase string.
+    pub fn serialize<S>(value: &Ulid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut buffer = [0; ULID_LEN];
+        value.array_to_str(&mut buffer);
+        buffer.make_ascii_lowercase();
+        // the buffer only contains ASCII characters
+        serializer.serialize_str(core::str::from_utf8(&buffer).unwrap())
+    }
+
+    /// Deserializes a ULID from a string.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Ulid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(UlidVisitor)
+    }
+
+    option_and_seq!();
+}
+
+/// Serialization and deserialization of ULIDs through their inner u128 type as a decimal string.
+///
+/// Many JSON readers, including JavaScript, lose precision on numbers this large, so this module
+/// is preferable to [`ulid_as_u128`] for JSON.
+///
+/// `Option<Ulid>` and `Vec<Ulid>` fields use `ulid_as_u128_string::option` and
+/// `ulid_as_u128_string::seq`.
+///
+/// # Examples
+/// ```
+/// # use ulid::Ulid;
+/// # use ulid::serde::ulid_as_u128_string;
+/// # use serde_derive::{Serialize, Deserialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct U128StringExample {
+///     #[serde(with = "ulid_as_u128_string")]
+///     identifier: Ulid
+/// }
+/// ```
+pub mod ulid_as_u128_string {
+    use crate::Ulid;
+    use core::fmt;
+    use serde::de::{self, Visitor};
+    use serde::{Deserializer, Serializer};
+
+    /// Serializes a ULID as a decimal string.
+    pub fn serialize<S>(value: &Ulid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&value.0)
+    }
+
+    /// Deserializes a ULID from a decimal string.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Ulid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(U128StringVisitor)
+    }
+
+    struct U128StringVisitor;
+
+    impl Visitor<'_> for U128StringVisitor {
+        type Value = Ulid;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a decimal u128 string")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Ulid, E> {
+            value
+                .parse()
+                .map(Ulid)
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+        }
+    }
+
+    option_and_seq!();
+}
+
+/// Serialization and deserialization of ULIDs as a struct of their timestamp and random parts.
+///
+/// The struct has a `timestamp_ms` field with the milliseconds since the unix epoch and a
+/// `random` field with the 80 random bits, as returned by
+/// [`Ulid::timestamp_ms`](crate::Ulid::timestamp_ms) and [`Ulid::random`](crate::Ulid::random).
+///
+/// `Option<Ulid>` and `Vec<Ulid>` fields use `ulid_as_timestamp_and_random::option` and
+/// `ulid_as_timestamp_and_random::seq`.
+///
+/// # Examples
+/// ```
+/// # use ulid::Ulid;
+/// # use ulid::serde::ulid_as_timestamp_and_random;
+/// # use serde_derive::{Serialize, Deserialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct PartsExample {
+///     #[serde(with = "ulid_as_timestamp_and_random")]
+///     identifier: Ulid
+/// }
+/// ```
+pub mod ulid_as_timestamp_and_random {
+    use crate::{bitmask, Ulid};
+    use core::fmt;
+    use serde::de::{self, MapAccess, SeqAccess, Visitor};
+    use serde::ser::SerializeStruct;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    const FIELDS: &[&str] = &["timestamp_ms", "random"];
+
+    /// Serializes a ULID as a struct of its timestamp and random parts.
+    pub fn serialize<S>(value: &Ulid, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut parts = serializer.serialize_struct("Ulid", 2)?;
+        parts.serialize_field("timestamp_ms", &value.timestamp_ms())?;
+        parts.serialize_field("random", &value.random())?;
+        parts.end()
+    }
+
+    /// Deserializes a ULID from a struct of its timestamp and random parts.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Ulid, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct("Ulid", FIELDS, PartsVisitor)
+    }
+
+    fn from_parts<E: de::Error>(timestamp_ms: u64, random: u128) -> Result<Ulid, E> {
+        if u128::from(timestamp_ms) > bitmask!(Ulid::TIME_BITS) {
+            return Err(E::custom("timestamp_ms does not fit in 48 bits"));
+        }
+        if random > bitmask!(Ulid::RAND_BITS) {
+            return Err(E::custom("random does not fit in 80 bits"));
+        }
+        Ok(Ulid::from_parts(timestamp_ms, random))
+    }
+
+    enum Field {
+        TimestampMs,
+        Random,
+    }
+
+    impl<'de> Deserialize<'de> for Field {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct FieldVisitor;
+
+            impl Visitor<'_> for FieldVisitor {
+                type Value = Field;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    formatter.write_str("`timestamp_ms` or `random`")
+                }
+
+                fn visit_str<E: de::Error>(self, value: &str) -> Result<Field, E> {
+                    match value {
+                        "timestamp_ms" => Ok(Field::TimestampMs),
+                        "random" => Ok(Field::Random),
+                        _ => Err(E::unknown_field(value, FIELDS)),
+                    }
+                }
+            }
+
+            deserializer.deserialize_identifier(FieldVisitor)
+        }
+    }
+
+    struct PartsVisitor;
+
+    impl<'de> Visitor<'de> for PartsVisitor {
+        type Value = Ulid;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a struct with `timestamp_ms` and `random`")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Ulid, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let timestamp_ms = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            let random = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+            from_parts(timestamp_ms, random)
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Ulid, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut timestamp_ms = None;
+            let mut random = None;
+            while let Some(field) = map.next_key()? {
+                match field {
+                    Field::TimestampMs if timestamp_ms.is_some() => {
+                        return Err(de::Error::duplicate_field("timestamp_ms"))
+                    }
+                    Field::TimestampMs => timestamp_ms = Some(map.next_value()?),
+                    Field::Random if random.is_some() => {
+                        return Err(de::Error::duplicate_field("random"))
+                    }
+                    Field::Random => random = Some(map.next_value()?),
+                }
+            }
+            let timestamp_ms =
+                timestamp_ms.ok_or_else(|| de::Error::missing_field("timestamp_ms"))?;
+            let random = random.ok_or_else(|| de::Error::missing_field("random"))?;
+            from_parts(timestamp_ms, random)
+        }
+    }
+
+    option_and_seq!();
+}
+
+/// Declares a marker type for `serde_with` which uses the functions of an adapter module
+#[cfg(feature = "serde_with")]
+macro_rules! serde_as_marker {
+    ($(#[$attr:meta])* $marker:ident, $module:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, Default)]
+        pub struct $marker;
+
+        impl serde_with::SerializeAs<Ulid> for $marker {
+            fn serialize_as<S>(source: &Ulid, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                $module::serialize(source, serializer)
+            }
+        }
+
+        impl<'de> serde_with::DeserializeAs<'de, Ulid> for $marker {
+            fn deserialize_as<D>(deserializer: D) -> Result<Ulid, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                $module::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "serde_with")]
+serde_as_marker!(
+    /// A `serde_with` adapter which uses [`ulid_as_u128`], for Ulids nested in containers.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::collections::HashMap;
+    /// # use ulid::Ulid;
+    /// # use ulid::serde::UlidAsU128;
+    /// # use serde_derive::{Serialize, Deserialize};
+    /// # use serde_with::serde_as;
+    /// #[serde_as]
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Graph {
+    ///     #[serde_as(as = "HashMap<UlidAsU128, Vec<Option<UlidAsU128>>>")]
+    ///     edges: HashMap<Ulid, Vec<Option<Ulid>>>,
+    /// }
+    /// ```
+    UlidAsU128,
+    ulid_as_u128
+);
+
+#[cfg(feature = "serde_with")]
+serde_as_marker!(
+    /// A `serde_with` adapter which uses [`ulid_as_bytes`], for Ulids nested in containers.
+    UlidAsBytes,
+    ulid_as_bytes
+);
+
+#[cfg(all(feature = "serde_with", feature = "uuid"))]
+serde_as_marker!(
+    /// A `serde_with` adapter which uses [`ulid_as_uuid`], for Ulids nested in containers.
+    UlidAsUuid,
+    ulid_as_uuid
+);
+
+#[cfg(feature = "serde_with")]
+serde_as_marker!(
+    /// A `serde_with` adapter which uses [`ulid_as_lowercase`], for Ulids nested in containers.
+    UlidAsLowercase,
+    ulid_as_lowercase
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_test::{assert_de_tokens, assert_tokens, Configure, Token};
+
+    const TEXT: &str = "01D39ZY06FGSCTVN4T2V9PKHFZ";
+    static BYTES: [u8; 16] = [
+        0x01, 0x68, 0xd3, 0xff, 0x00, 0xcf, 0x86, 0x59, 0xad, 0xd4, 0x9a, 0x16, 0xd3, 0x69, 0xc5,
+        0xff,
+    ];
+    const ULID: Ulid = Ulid::from_bytes(BYTES);
+
+    #[test]
+    fn test_fixture() {
+        assert_eq!(ULID.to_string(), TEXT);
+    }
+
+    #[test]
+    fn test_readable() {
+        assert_tokens(&ULID.readable(), &[Token::Str(TEXT)]);
+        assert_de_tokens(&ULID.readable(), &[Token::BorrowedStr(TEXT)]);
+        assert_de_tokens(&ULID.readable(), &[Token::String(TEXT)]);
+    }
+
+    #[test]
+    fn test_compact() {
+        assert_tokens(&ULID.compact(), &[Token::Bytes(&BYTES)]);
+        assert_de_tokens(&ULID.compact(), &[Token::BorrowedBytes(&BYTES)]);
+        assert_de_tokens(&ULID.compact(), &[Token::Bytes(TEXT.as_bytes())]);
+    }
+
+    #[test]
+    fn test_seq_and_u128() {
+        let mut tokens = vec![Token::Seq { len: Some(16) }];
+        tokens.extend(ULID.to_bytes().iter().map(|&byte| Token::U8(byte)));
+        tokens.push(Token::SeqEnd);
+        assert_de_tokens(&ULID.compact(), &tokens);
+
+        let value = UlidVisitor.visit_u128::<de::value::Error>(ULID.0);
+        assert_eq!(value, Ok(ULID));
+    }
+
+    #[test]
+    fn test_formats() {
+        let json = serde_json::to_string(&ULID).unwrap();
+        assert_eq!(json, format!("\"{}\"", TEXT));
+        assert_eq!(serde_json::from_str::<Ulid>(&json).unwrap(), ULID);
+
+        let binary = bincode::serialize(&ULID).unwrap();
+        assert_eq!(binary.len(), 8 + 16);
+        assert_eq!(bincode::deserialize::<Ulid>(&binary).unwrap(), ULID);
+
+        // data written as a string before the binary form existed
+        let legacy = bincode::serialize(TEXT).unwrap();
+        assert_eq!(bincode::deserialize::<Ulid>(&legacy).unwrap(), ULID);
+    }
+
+    #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+    struct Shapes {
+        #[serde(with = "ulid_as_hex")]
+        one: Ulid,
+        #[serde(with = "ulid_as_hex::option")]
+        some: Option<Ulid>,
+        #[serde(with = "ulid_as_hex::option")]
+        none: Option<Ulid>,
+        #[serde(with = "ulid_as_hex::seq")]
+        many: Vec<Ulid>,
+    }
+
+    #[test]
+    fn test_field_shapes() {
+        let shapes = Shapes {
+            one: ULID,
+            some: Some(ULID),
+            none: None,
+            many: vec![ULID, Ulid::nil()],
+        };
+        let hex = &format!("{:032x}", ULID.0);
+        let json = serde_json::to_string(&shapes).unwrap();
+        assert_eq!(
+            json,
+            format!(
+                r#"{{"one":"{0}","some":"{0}","none":null,"many":["{0}","{1}"]}}"#,
+                hex, "00000000000000000000000000000000"
+            )
+        );
+        assert_eq!(serde_json::from_str::<Shapes>(&json).unwrap(), shapes);
+        let upper = json.replace(hex, &hex.to_uppercase());
+        assert_eq!(serde_json::from_str::<Shapes>(&upper).unwrap(), shapes);
+    }
+
+    fn round_trip<F, G>(serialize: F, deserialize: G, expected: &str)
+    where
+        F: FnOnce(&Ulid, &mut serde_json::Serializer<&mut Vec<u8>>) -> serde_json::Result<()>,
+        G: FnOnce(
+            &mut serde_json::Deserializer<serde_json::de::StrRead<'_>>,
+        ) -> serde_json::Result<Ulid>,
+    {
+        let mut json = Vec::new();
+        serialize(&ULID, &mut serde_json::Serializer::new(&mut json)).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert_eq!(json, expected);
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        assert_eq!(deserialize(&mut deserializer).unwrap(), ULID);
+    }
+
+    #[test]
+    fn test_adapters() {
+        round_trip(
+            |value, s| ulid_as_lowercase::serialize(value, s),
+            |d| ulid_as_lowercase::deserialize(d),
+            "\"01d39zy06fgsctvn4t2v9pkhfz\"",
+        );
+        round_trip(
+            |value, s| ulid_as_u128_string::serialize(value, s),
+            |d| ulid_as_u128_string::deserialize(d),
+            &format!("\"{}\"", ULID.0),
+        );
+        round_trip(
+            |value, s| ulid_as_timestamp_and_random::serialize(value, s),
+            |d| ulid_as_timestamp_and_random::deserialize(d),
+            &format!(
+                r#"{{"timestamp_ms":{},"random":{}}}"#,
+                ULID.timestamp_ms(),
+                ULID.random()
+            ),
+        );
+        let bytes = ULID.to_bytes().map(|byte| byte.to_string()).join(",");
+        round_trip(
+            |value, s| ulid_as_bytes::serialize(value, s),
+            |d| ulid_as_bytes::deserialize(d),
+            &format!("[{}]", bytes),
+        );
+    }
+
+    #[test]
+    fn test_adapter_errors() {
+        let mut d = serde_json::Deserializer::from_str("\"0168d3ff00cf8659add49a16d369c5f\"");
+        assert!(ulid_as_hex::deserialize(&mut d).is_err());
+        let mut d = serde_json::Deserializer::from_str(r#"{"timestamp_ms":1,"random":1e30}"#);
+        assert!(ulid_as_timestamp_and_random::deserialize(&mut d).is_err());
+        let json = format!(r#"{{"timestamp_ms":1,"random":{}}}"#, 1u128 << 80);
+        let mut d = serde_json::Deserializer::from_str(&json);
+        assert!(ulid_as_timestamp_and_random::deserialize(&mut d).is_err());
+    }
+
+    #[cfg(feature = "serde_with")]
+    #[test]
+    fn test_serde_with_markers() {
+        use serde_with::serde_as;
+        use std::collections::BTreeMap;
+
+        #[serde_as]
+        #[derive(Debug, PartialEq, serde_derive::Serialize, serde_derive::Deserialize)]
+        struct Nested {
+            #[serde_as(as = "BTreeMap<UlidAsLowercase, Vec<Option<UlidAsU128>>>")]
+            edges: BTreeMap<Ulid, Vec<Option<Ulid>>>,
+            #[serde_as(as = "Option<UlidAsBytes>")]
+            bytes: Option<Ulid>,
+        }
+
+        let nested = Nested {
+            edges: vec![(ULID, vec![Some(Ulid(1)), None])]
+                .into_iter()
+                .collect(),
+            bytes: Some(Ulid(2)),
+        };
+        let json = serde_json::to_string(&nested).unwrap();
+        assert_eq!(
+            json,
+            r#"{"edges":{"01d39zy06fgsctvn4t2v9pkhfz":[1,null]},"bytes":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,2]}"#
+        );
+        assert_eq!(serde_json::from_str::<Nested>(&json).unwrap(), nested);
+    }
 }
@@ -0,0 +1,365 @@
+//! A monotonic generator shared between processes through a memory-mapped file.
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use std::{hint, thread};
+
+use fs4::FileExt;
+use memmap2::MmapRaw;
+
+use crate::{Generator, OverflowError, OverflowPolicy, Ulid};
+
+const MAGIC: &[u8; 8] = b"ULIDSHM\0";
+const VERSION: u32 = 1;
+/// magic, version, reserved, last Ulid and checksum
+const LEN: usize = 40;
+
+/// A monotonic Ulid generator whose state is shared by every process opening the same file
+///
+/// The last Ulid issued is kept in a small memory-mapped file. Each generation takes an exclusive
+/// lock on the file, and threads sharing one `SharedGenerator` are serialized by a mutex, so Ulids
+/// are strictly increasing across all processes and threads on the host, as if they shared one
+/// [`Generator`]. Overflow is handled with the generator's [`OverflowPolicy`].
+/// While waiting for the next millisecond the lock is released.
+///
+/// The file carries a magic number, a layout version and a checksum. A file written by an
+/// incompatible version is reported as [`SharedError::Stale`], and a damaged one as
+/// [`SharedError::Corrupt`]. [`SharedGenerator::create`] resets such a file, at the cost of the
+/// monotonic guarantee with respect to Ulids issued before.
+///
+/// # Example
+/// ```rust
+/// use ulid::SharedGenerator;
+///
+/// let path = std::env::temp_dir().join(format!("ulid-doc-{}", std::process::id()));
+/// let generator = SharedGenerator::open(&path).unwrap();
+///
+/// let ulid1 = generator.generate().unwrap();
+/// let ulid2 = SharedGenerator::open(&path).unwrap().generate().unwrap();
+///
+/// assert!(ulid1 < ulid2);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct SharedGenerator {
+    file: File,
+    map: MmapRaw,
+    policy: OverflowPolicy,
+    /// Serializes the threads of this process, which a file lock does not exclude
+    threads: Mutex<()>,
+}
+
+/// Holds the mutex and the file lock, and releases both when dropped
+struct Locked<'a> {
+    file: &'a File,
+    _thread: MutexGuard<'a, ()>,
+}
+
+impl Drop for Locked<'_> {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(self.file);
+    }
+}
+
+impl SharedGenerator {
+    /// Open the shared state at `path`, initializing the file if it does not exist or is empty
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<SharedGenerator, SharedError> {
+        SharedGenerator::open_with(path.as_ref(), false)
+    }
+
+    /// Open the shared state at `path`, resetting any existing state
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<SharedGenerator, SharedError> {
+        SharedGenerator::open_with(path.as_ref(), true)
+    }
+
+    fn open_with(path: &Path, reset: bool) -> Result<SharedGenerator, SharedError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        FileExt::lock(&file)?;
+        let result = SharedGenerator::init(file, reset);
+        if let Ok(generator) = &result {
+            FileExt::unlock(&generator.file)?;
+        }
+        result
+    }
+
+    /// Validate or initialize the file, which must be locked
+    fn init(file: File, reset: bool) -> Result<SharedGenerator, SharedError> {
+        let len = file.metadata()?.len();
+        let fresh = reset || len == 0;
+        if fresh {
+            file.set_len(LEN as u64)?;
+        } else if len != LEN as u64 {
+            return Err(SharedError::Corrupt);
+        }
+
+        let map = MmapRaw::map_raw(&file)?;
+        let generator = SharedGenerator {
+            file,
+            map,
+            policy: OverflowPolicy::Error,
+            threads: Mutex::new(()),
+        };
+        if fresh {
+            generator.store(Ulid::nil())?;
+        } else {
+            generator.load()?;
+        }
+        Ok(generator)
+    }
+
+    /// Gets the overflow policy of this generator
+    pub const fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Sets the overflow policy of this generator
+    pub fn set_policy(&mut self, policy: OverflowPolicy) {
+        self.policy = policy;
+    }
+
+    /// Generate a new Ulid. Each call is guaranteed to provide a Ulid with a larger value than the
+    /// last call from any process sharing the file.
+    ///
+    /// Only [`OverflowPolicy::Error`] can make this method fail with [`SharedError::Overflow`].
+    pub fn generate(&self) -> Result<Ulid, SharedError> {
+        self.generate_with_source(&mut crate::time_utils::rng())
+    }
+
+    /// Generate a new Ulid with the given source. See [`SharedGenerator::generate`].
+    pub fn generate_with_source<R>(&self, source: &mut R) -> Result<Ulid, SharedError>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        loop {
+            let result = {
+                let _locked = self.lock()?;
+                self.generate_locked(source)
+            };
+            if let Some(result) = result.transpose() {
+                return result;
+            }
+            match self.policy {
+                OverflowPolicy::Spin => hint::spin_loop(),
+                _ => thread::sleep(Duration::from_millis(1)),
+            }
+        }
+    }
+
+    /// Generate while holding the lock, returning `None` to wait for the next millisecond
+    fn generate_locked<R>(&self, source: &mut R) -> Result<Option<Ulid>, SharedError>
+    where
+        R: rand::Rng + ?Sized,
+    {
+        let mut generator = Generator::resume(self.load()?, self.policy);
+        let next = match generator.generate_with_source(source) {
+            Ok(next) => next,
+            Err(overflow) => match self.policy {
+                OverflowPolicy::Error => return Err(SharedError::Overflow(overflow.into())),
+                OverflowPolicy::Spin | OverflowPolicy::Sleep => return Ok(None),
+                OverflowPolicy::BorrowFuture => overflow.commit_overflow_increment(),
+                OverflowPolicy::Random => overflow.commit_overflow_random_with_source(source),
+            },
+        };
+        self.store(next)?;
+        Ok(Some(next))
+    }
+
+    /// The last Ulid issued through the shared file
+    pub fn last(&self) -> Result<Ulid, SharedError> {
+        let _locked = self.lock()?;
+        self.load()
+    }
+
+    /// Take the mutex, then the file lock
+    fn lock(&self) -> Result<Locked<'_>, SharedError> {
+        // the state lives in the checksummed file, so a panic while locked leaves nothing behind
+        let thread = self.threads.lock().unwrap_or_else(PoisonError::into_inner);
+        FileExt::lock(&self.file)?;
+        Ok(Locked {
+            file: &self.file,
+            _thread: thread,
+        })
+    }
+
+    fn load(&self) -> Result<Ulid, SharedError> {
+        let mut map = [0; LEN];
+        // SAFETY: the map is LEN bytes long and only written while holding the mutex and file lock
+        unsafe { std::ptr::copy_nonoverlapping(self.map.as_ptr(), map.as_mut_ptr(), LEN) };
+        if &map[0..8] != MAGIC || checksum(&map[..32]) != read_u64(&map[32..40]) {
+            return Err(SharedError::Corrupt);
+        }
+        let version = u32::from_le_bytes([map[8], map[9], map[10], map[11]]);
+        if version != VERSION {
+            return Err(SharedError::Stale { version });
+        }
+        let mut bytes = [0; 16];
+        bytes.copy_from_slice(&map[16..32]);
+        Ok(Ulid::from_bytes(bytes))
+    }
+
+    fn store(&self, ulid: Ulid) -> Result<(), SharedError> {
+        let mut data = [0; LEN];
+        data[0..8].copy_from_slice(MAGIC);
+        data[8..12].copy_from_slice(&VERSION.to_le_bytes());
+        data[16..32].copy_from_slice(&ulid.to_bytes());
+        let sum = checksum(&data[..32]);
+        data[32..40].copy_from_slice(&sum.to_le_bytes());
+
+        // SAFETY: the map is LEN bytes long and only written while holding the mutex and file lock
+        unsafe { std::ptr::copy_nonoverlapping(data.as_ptr(), self.map.as_mut_ptr(), LEN) };
+        self.map.flush_async()?;
+        Ok(())
+    }
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0; 8];
+    buf.copy_from_slice(bytes);
+    u64::from_le_bytes(buf)
+}
+
+/// FNV-1a
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// An error that can occur when using a [`SharedGenerator`]
+#[derive(Debug)]
+pub enum SharedError {
+    /// The shared file could not be opened, locked or mapped
+    Io(io::Error),
+    /// The shared file does not contain valid state
+    Corrupt,
+    /// The shared file was written with an incompatible layout version
+    Stale {
+        /// The layout version found in the file
+        version: u32,
+    },
+    /// The random bits would overflow into the next millisecond
+    Overflow(OverflowError),
+}
+
+impl From<io::Error> for SharedError {
+    fn from(err: io::Error) -> Self {
+        SharedError::Io(err)
+    }
+}
+
+impl std::error::Error for SharedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SharedError::Io(err) => Some(err),
+            SharedError::Overflow(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SharedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            SharedError::Io(err) => write!(f, "shared generator io error: {}", err),
+            SharedError::Corrupt => write!(f, "shared generator state is corrupt"),
+            SharedError::Stale { version } => write!(
+                f,
+                "shared generator state has version {}, expected {}",
+                version, VERSION
+            ),
+            SharedError::Overflow(err) => err.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ulid-shared-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_shared_between_handles() {
+        let path = temp_path("handles");
+        let generator1 = SharedGenerator::create(&path).unwrap();
+        let generator2 = SharedGenerator::open(&path).unwrap();
+
+        let mut previous = Ulid::nil();
+        for i in 0..100 {
+            let generator = if i % 2 == 0 { &generator1 } else { &generator2 };
+            let next = generator.generate().unwrap();
+            assert!(next > previous);
+            previous = next;
+        }
+        assert_eq!(generator1.last().unwrap(), previous);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_overflow_policy() {
+        let path = temp_path("overflow");
+        let mut generator = SharedGenerator::create(&path).unwrap();
+        let ahead = crate::time_utils::now() + Duration::from_secs(3600);
+        let maxed = Ulid::from_parts(
+            Ulid::from_datetime(ahead).timestamp_ms(),
+            crate::bitmask!(Ulid::RAND_BITS),
+        );
+        generator.store(maxed).unwrap();
+
+        match generator.generate() {
+            Err(SharedError::Overflow(err)) => assert_eq!(err.previous(), maxed),
+            other => panic!("expected overflow, got {:?}", other),
+        }
+
+        generator.set_policy(OverflowPolicy::BorrowFuture);
+        let next = generator.generate().unwrap();
+        assert_eq!(next.timestamp_ms(), maxed.timestamp_ms() + 1);
+        assert_eq!(next.random(), 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_detects_corrupt_and_stale() {
+        let path = temp_path("corrupt");
+        SharedGenerator::create(&path).unwrap().generate().unwrap();
+
+        let mut data = std::fs::read(&path).unwrap();
+        data[20] ^= 0xff;
+        std::fs::write(&path, &data).unwrap();
+        assert!(matches!(
+            SharedGenerator::open(&path),
+            Err(SharedError::Corrupt)
+        ));
+
+        data[20] ^= 0xff;
+        data[8] = 7;
+        let sum = checksum(&data[..32]);
+        data[32..40].copy_from_slice(&sum.to_le_bytes());
+        std::fs::write(&path, &data).unwrap();
+        assert!(matches!(
+            SharedGenerator::open(&path),
+            Err(SharedError::Stale { version: 7 })
+        ));
+
+        std::fs::write(&path, b"short").unwrap();
+        assert!(matches!(
+            SharedGenerator::open(&path),
+            Err(SharedError::Corrupt)
+        ));
+
+        assert!(SharedGenerator::create(&path).unwrap().generate().is_ok());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
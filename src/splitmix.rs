@@ -0,0 +1,80 @@
+use core::convert::Infallible;
+
+use rand::{rand_core::utils, TryRng};
+
+/// SplitMix64, a small and fast generator whose output only depends on the seed
+///
+/// Unlike the generators in `rand`, its sequence is fixed here, so it is stable across platforms
+/// and crate versions. It is not cryptographically secure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) const fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    /// Derive an independent generator for the given stream, e.g. a chunk or thread index
+    #[cfg(feature = "rayon")]
+    pub(crate) fn for_stream(seed: u64, stream: u64) -> Self {
+        let mut base = SplitMix64::new(seed);
+        let stream_seed = base.next() ^ mix(stream.wrapping_add(0x9e37_79b9_7f4a_7c15));
+        SplitMix64::new(stream_seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        mix(self.state)
+    }
+}
+
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl TryRng for SplitMix64 {
+    type Error = Infallible;
+
+    #[inline]
+    fn try_next_u32(&mut self) -> Result<u32, Self::Error> {
+        Ok((self.next() >> 32) as u32)
+    }
+
+    #[inline]
+    fn try_next_u64(&mut self) -> Result<u64, Self::Error> {
+        Ok(self.next())
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Self::Error> {
+        utils::fill_bytes_via_next_word(dest, || self.try_next_u64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_reference_sequence() {
+        // reference values of SplitMix64 seeded with 1234567
+        let mut rng = SplitMix64::new(1234567);
+        assert_eq!(rng.next_u64(), 6457827717110365317);
+        assert_eq!(rng.next_u64(), 3203168211198807973);
+        assert_eq!(rng.next_u64(), 9817491932198370423);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_streams_differ() {
+        let mut a = SplitMix64::for_stream(42, 0);
+        let mut b = SplitMix64::for_stream(42, 1);
+        assert_ne!(a.next_u64(), b.next_u64());
+        assert_eq!(SplitMix64::for_stream(42, 1), SplitMix64::for_stream(42, 1));
+    }
+}
@@ -0,0 +1,116 @@
+//! Wrappers which select how a Ulid is stored in a database column.
+//!
+//! A plain [`Ulid`] uses the most compact column type of each database, usually 16 bytes or a
+//! `uuid`, and decodes from any of the supported column types. Wrap it in [`UlidText`] or
+//! [`UlidBytes`] to pick the storage form explicitly.
+//!
+//! With the `sqlx-postgres`, `sqlx-mysql` and `sqlx-sqlite` features, or `sqlx` for all three,
+//! these types implement the `sqlx` traits for the following columns.
+//!
+//! | Database | `Ulid`       | `UlidText`   | `UlidBytes`  | Also decodes from         |
+//! |----------|--------------|--------------|--------------|---------------------------|
+//! | Postgres | `uuid`       | `text`       | `bytea`      | `varchar`, `char(n)`      |
+//! | MySQL    | `binary(16)` | `char(26)`   | `binary(16)` | `varchar`, `text`, `blob` |
+//! | SQLite   | `BLOB`       | `TEXT`       | `BLOB`       |                           |
+//!
+//! With the `diesel-postgres`, `diesel-mysql` and `diesel-sqlite` features, or `diesel` for all
+//! three, a plain [`Ulid`] can be used with the `Uuid` (Postgres only), `Binary` and `Text` SQL
+//! types, and the column type of the schema selects the storage form.
+//!
+//! With the `rusqlite` feature, [`Ulid`] and [`UlidBytes`] are stored in `BLOB` columns and
+//! [`UlidText`] in `TEXT` columns. All three decode from either. rusqlite 0.28 to 0.40 are
+//! supported, since only one `libsqlite3-sys` may be linked and it has to match any other SQLite
+//! crate in the dependency tree.
+//!
+//! With the `mysql` feature, [`Ulid`] and [`UlidBytes`] convert to 16-byte values for `binary(16)`
+//! columns through `mysql_common`, and [`UlidText`] to its string for `char(26)` columns. All
+//! three convert from either.
+
+use crate::Ulid;
+
+/// A Ulid stored as its 26-character canonical string, e.g. in a `char(26)` or `text` column
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UlidText(pub Ulid);
+
+/// A Ulid stored as 16 big-endian bytes, e.g. in a `binary(16)`, `bytea` or `blob` column
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UlidBytes(pub Ulid);
+
+impl From<Ulid> for UlidText {
+    fn from(ulid: Ulid) -> UlidText {
+        UlidText(ulid)
+    }
+}
+
+impl From<UlidText> for Ulid {
+    fn from(text: UlidText) -> Ulid {
+        text.0
+    }
+}
+
+impl From<Ulid> for UlidBytes {
+    fn from(ulid: Ulid) -> UlidBytes {
+        UlidBytes(ulid)
+    }
+}
+
+impl From<UlidBytes> for Ulid {
+    fn from(bytes: UlidBytes) -> Ulid {
+        bytes.0
+    }
+}
+
+/// Decode a Ulid from 16 bytes or the bytes of its string representation
+#[cfg(any(
+    feature = "sqlx-postgres",
+    feature = "sqlx-mysql",
+    feature = "sqlx-sqlite",
+    feature = "diesel-postgres",
+    feature = "diesel-mysql",
+    feature = "diesel-sqlite",
+    feature = "mysql"
+))]
+pub(crate) fn ulid_from_slice(
+    bytes: &[u8],
+) -> Result<Ulid, Box<dyn std::error::Error + Send + Sync>> {
+    use std::convert::TryFrom;
+
+    if let Ok(bytes) = <[u8; 16]>::try_from(bytes) {
+        return Ok(Ulid::from_bytes(bytes));
+    }
+    if bytes.len() != crate::ULID_LEN {
+        return Err(format!(
+            "invalid length: expected 16 bytes or {} characters for a ulid, got {} bytes",
+            crate::ULID_LEN,
+            bytes.len()
+        )
+        .into());
+    }
+    ulid_from_text(std::str::from_utf8(bytes)?)
+}
+
+/// Decode a Ulid from its string representation, ignoring the padding of `char(n)` columns
+pub(crate) fn ulid_from_text(text: &str) -> Result<Ulid, Box<dyn std::error::Error + Send + Sync>> {
+    let text = text.trim_end_matches(' ');
+    if text.len() != crate::ULID_LEN {
+        return Err(format!(
+            "invalid length: expected 16 bytes or {} characters for a ulid, got {}",
+            crate::ULID_LEN,
+            text.len()
+        )
+        .into());
+    }
+    Ok(Ulid::from_string(text)?)
+}
+
+/// The Ulid used by the tests of the SQL integrations
+#[cfg(test)]
+pub(crate) mod fixture {
+    use crate::Ulid;
+
+    pub const TEXT: &str = "01D39ZY06FGSCTVN4T2V9PKHFZ";
+
+    pub fn ulid() -> Ulid {
+        Ulid::from_string(TEXT).unwrap()
+    }
+}
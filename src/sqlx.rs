@@ -0,0 +1,371 @@
+//! `sqlx` support for Postgres, MySQL and SQLite.
+
+use sqlx::database::Database;
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::{Decode, Encode, Type};
+
+use crate::sql::{ulid_from_slice, ulid_from_text, UlidBytes, UlidText};
+use crate::{Ulid, ULID_LEN};
+
+/// Encode through the representation of another type
+fn encode_as<'q, DB, T>(
+    value: T,
+    buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+) -> Result<IsNull, BoxDynError>
+where
+    DB: Database,
+    T: Encode<'q, DB>,
+{
+    value.encode_by_ref(buf)
+}
+
+fn text(ulid: &Ulid) -> String {
+    let mut buffer = [0; ULID_LEN];
+    ulid.array_to_str(&mut buffer).to_owned()
+}
+
+/// The column types accepted by every representation, and the decoding shared by the wrappers
+macro_rules! decode_wrappers {
+    ($db:ty) => {
+        impl<'r> Decode<'r, $db> for UlidText {
+            fn decode(value: <$db as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+                <Ulid as Decode<'r, $db>>::decode(value).map(UlidText)
+            }
+        }
+
+        impl<'r> Decode<'r, $db> for UlidBytes {
+            fn decode(value: <$db as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+                <Ulid as Decode<'r, $db>>::decode(value).map(UlidBytes)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "sqlx-postgres")]
+mod postgres {
+    use super::*;
+    use sqlx::postgres::types::Oid;
+    use sqlx::postgres::{PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+    use sqlx::ValueRef;
+
+    const UUID: PgTypeInfo = PgTypeInfo::with_oid(Oid(2950));
+    const UUID_ARRAY: PgTypeInfo = PgTypeInfo::with_oid(Oid(2951));
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        *ty == UUID
+            || <&str as Type<Postgres>>::compatible(ty)
+            || <&[u8] as Type<Postgres>>::compatible(ty)
+    }
+
+    impl Type<Postgres> for Ulid {
+        fn type_info() -> PgTypeInfo {
+            UUID
+        }
+
+        fn compatible(ty: &PgTypeInfo) -> bool {
+            compatible(ty)
+        }
+    }
+
+    impl PgHasArrayType for Ulid {
+        fn array_type_info() -> PgTypeInfo {
+            UUID_ARRAY
+        }
+
+        fn array_compatible(ty: &PgTypeInfo) -> bool {
+            [
+                UUID_ARRAY,
+                <&str as PgHasArrayType>::array_type_info(),
+                <&[u8] as PgHasArrayType>::array_type_info(),
+            ]
+            .contains(ty)
+        }
+    }
+
+    impl Encode<'_, Postgres> for Ulid {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <Postgres as Database>::ArgumentBuffer<'_>,
+        ) -> Result<IsNull, BoxDynError> {
+            encode_as::<Postgres, _>(&self.to_bytes()[..], buf)
+        }
+    }
+
+    impl<'r> Decode<'r, Postgres> for Ulid {
+        fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+            let ty = value.type_info().into_owned();
+            if <&str as Type<Postgres>>::compatible(&ty) {
+                return ulid_from_text(value.as_str()?);
+            }
+            if ty == UUID && value.format() == PgValueFormat::Text {
+                // the hyphenated form of the uuid
+                let hex: String = value.as_str()?.chars().filter(|c| *c != '-').collect();
+                if hex.len() != 32 {
+                    return Err(format!("invalid uuid for a ulid: {}", value.as_str()?).into());
+                }
+                return Ok(Ulid(u128::from_str_radix(&hex, 16)?));
+            }
+            ulid_from_slice(&<Vec<u8> as Decode<Postgres>>::decode(value)?)
+        }
+    }
+
+    impl Type<Postgres> for UlidText {
+        fn type_info() -> PgTypeInfo {
+            <&str as Type<Postgres>>::type_info()
+        }
+
+        fn compatible(ty: &PgTypeInfo) -> bool {
+            compatible(ty)
+        }
+    }
+
+    impl PgHasArrayType for UlidText {
+        fn array_type_info() -> PgTypeInfo {
+            <&str as PgHasArrayType>::array_type_info()
+        }
+
+        fn array_compatible(ty: &PgTypeInfo) -> bool {
+            <Ulid as PgHasArrayType>::array_compatible(ty)
+        }
+    }
+
+    impl Encode<'_, Postgres> for UlidText {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <Postgres as Database>::ArgumentBuffer<'_>,
+        ) -> Result<IsNull, BoxDynError> {
+            encode_as::<Postgres, _>(text(&self.0), buf)
+        }
+    }
+
+    impl Type<Postgres> for UlidBytes {
+        fn type_info() -> PgTypeInfo {
+            <&[u8] as Type<Postgres>>::type_info()
+        }
+
+        fn compatible(ty: &PgTypeInfo) -> bool {
+            compatible(ty)
+        }
+    }
+
+    impl PgHasArrayType for UlidBytes {
+        fn array_type_info() -> PgTypeInfo {
+            <&[u8] as PgHasArrayType>::array_type_info()
+        }
+
+        fn array_compatible(ty: &PgTypeInfo) -> bool {
+            <Ulid as PgHasArrayType>::array_compatible(ty)
+        }
+    }
+
+    impl Encode<'_, Postgres> for UlidBytes {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <Postgres as Database>::ArgumentBuffer<'_>,
+        ) -> Result<IsNull, BoxDynError> {
+            encode_as::<Postgres, _>(&self.0.to_bytes()[..], buf)
+        }
+    }
+
+    decode_wrappers!(Postgres);
+}
+
+#[cfg(feature = "sqlx-mysql")]
+mod mysql {
+    use super::*;
+    use sqlx::mysql::{MySql, MySqlTypeInfo, MySqlValueRef};
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        <&[u8] as Type<MySql>>::compatible(ty) || <&str as Type<MySql>>::compatible(ty)
+    }
+
+    impl Type<MySql> for Ulid {
+        fn type_info() -> MySqlTypeInfo {
+            <&[u8] as Type<MySql>>::type_info()
+        }
+
+        fn compatible(ty: &MySqlTypeInfo) -> bool {
+            compatible(ty)
+        }
+    }
+
+    impl Encode<'_, MySql> for Ulid {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <MySql as Database>::ArgumentBuffer<'_>,
+        ) -> Result<IsNull, BoxDynError> {
+            encode_as::<MySql, _>(&self.to_bytes()[..], buf)
+        }
+    }
+
+    impl<'r> Decode<'r, MySql> for Ulid {
+        fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+            ulid_from_slice(<&[u8] as Decode<MySql>>::decode(value)?)
+        }
+    }
+
+    impl Type<MySql> for UlidText {
+        fn type_info() -> MySqlTypeInfo {
+            <&str as Type<MySql>>::type_info()
+        }
+
+        fn compatible(ty: &MySqlTypeInfo) -> bool {
+            compatible(ty)
+        }
+    }
+
+    impl Encode<'_, MySql> for UlidText {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <MySql as Database>::ArgumentBuffer<'_>,
+        ) -> Result<IsNull, BoxDynError> {
+            encode_as::<MySql, _>(text(&self.0), buf)
+        }
+    }
+
+    impl Type<MySql> for UlidBytes {
+        fn type_info() -> MySqlTypeInfo {
+            <Ulid as Type<MySql>>::type_info()
+        }
+
+        fn compatible(ty: &MySqlTypeInfo) -> bool {
+            compatible(ty)
+        }
+    }
+
+    impl Encode<'_, MySql> for UlidBytes {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <MySql as Database>::ArgumentBuffer<'_>,
+        ) -> Result<IsNull, BoxDynError> {
+            <Ulid as Encode<MySql>>::encode_by_ref(&self.0, buf)
+        }
+    }
+
+    decode_wrappers!(MySql);
+}
+
+#[cfg(feature = "sqlx-sqlite")]
+mod sqlite {
+    use super::*;
+    use sqlx::sqlite::{Sqlite, SqliteTypeInfo, SqliteValueRef};
+
+    impl Type<Sqlite> for Ulid {
+        fn type_info() -> SqliteTypeInfo {
+            <&[u8] as Type<Sqlite>>::type_info()
+        }
+
+        fn compatible(ty: &SqliteTypeInfo) -> bool {
+            <&[u8] as Type<Sqlite>>::compatible(ty)
+        }
+    }
+
+    impl<'q> Encode<'q, Sqlite> for Ulid {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <Sqlite as Database>::ArgumentBuffer<'q>,
+        ) -> Result<IsNull, BoxDynError> {
+            encode_as::<Sqlite, _>(self.to_bytes().to_vec(), buf)
+        }
+    }
+
+    impl<'r> Decode<'r, Sqlite> for Ulid {
+        fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+            ulid_from_slice(<&[u8] as Decode<Sqlite>>::decode(value)?)
+        }
+    }
+
+    impl Type<Sqlite> for UlidText {
+        fn type_info() -> SqliteTypeInfo {
+            <&str as Type<Sqlite>>::type_info()
+        }
+
+        fn compatible(ty: &SqliteTypeInfo) -> bool {
+            <&[u8] as Type<Sqlite>>::compatible(ty)
+        }
+    }
+
+    impl<'q> Encode<'q, Sqlite> for UlidText {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <Sqlite as Database>::ArgumentBuffer<'q>,
+        ) -> Result<IsNull, BoxDynError> {
+            encode_as::<Sqlite, _>(text(&self.0), buf)
+        }
+    }
+
+    impl Type<Sqlite> for UlidBytes {
+        fn type_info() -> SqliteTypeInfo {
+            <Ulid as Type<Sqlite>>::type_info()
+        }
+
+        fn compatible(ty: &SqliteTypeInfo) -> bool {
+            <&[u8] as Type<Sqlite>>::compatible(ty)
+        }
+    }
+
+    impl<'q> Encode<'q, Sqlite> for UlidBytes {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <Sqlite as Database>::ArgumentBuffer<'q>,
+        ) -> Result<IsNull, BoxDynError> {
+            <Ulid as Encode<Sqlite>>::encode_by_ref(&self.0, buf)
+        }
+    }
+
+    decode_wrappers!(Sqlite);
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::sql::fixture::{ulid, TEXT};
+        use sqlx::{Connection, Row, SqliteConnection};
+
+        #[test]
+        fn test_sqlite_round_trip() {
+            futures::executor::block_on(round_trip());
+        }
+
+        async fn round_trip() {
+            let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+            sqlx::query("CREATE TABLE ids (id BLOB, label TEXT)")
+                .execute(&mut conn)
+                .await
+                .unwrap();
+
+            sqlx::query("INSERT INTO ids VALUES (?, ?)")
+                .bind(ulid())
+                .bind(UlidText(ulid()))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+
+            let row = sqlx::query("SELECT id, label, typeof(id), typeof(label) FROM ids")
+                .fetch_one(&mut conn)
+                .await
+                .unwrap();
+            assert_eq!(row.get::<Ulid, _>(0), ulid());
+            assert_eq!(row.get::<Ulid, _>(1), ulid());
+            assert_eq!(row.get::<UlidBytes, _>(1), UlidBytes(ulid()));
+            assert_eq!(row.get::<UlidText, _>(0), UlidText(ulid()));
+            assert_eq!(row.get::<Vec<u8>, _>(0), ulid().to_bytes());
+            assert_eq!(row.get::<String, _>(1), TEXT);
+            assert_eq!(row.get::<String, _>(2), "blob");
+            assert_eq!(row.get::<String, _>(3), "text");
+
+            let found: Option<Ulid> = sqlx::query_scalar("SELECT id FROM ids WHERE label = ?")
+                .bind(UlidText(ulid()))
+                .fetch_optional(&mut conn)
+                .await
+                .unwrap();
+            assert_eq!(found, Some(ulid()));
+
+            let err = sqlx::query_scalar::<_, Ulid>("SELECT x'0102'")
+                .fetch_one(&mut conn)
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("got 2 bytes"));
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Opt-in statistics for [`crate::Generator`].
+
+use std::time::Duration;
+
+/// Counters describing the work done by a [`crate::Generator`]
+///
+/// Statistics are only collected after [`crate::Generator::enable_stats`] is called.
+///
+/// # Example
+/// ```rust
+/// use ulid::Generator;
+///
+/// let mut generator = Generator::new();
+/// generator.enable_stats();
+///
+/// generator.generate().unwrap();
+/// generator.generate().unwrap();
+///
+/// let stats = generator.stats().unwrap();
+/// assert_eq!(stats.generated(), 2);
+/// assert!(stats.max_burst() >= 1);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GeneratorStats {
+    generated: u64,
+    increments: u64,
+    overflows: u64,
+    overflow_increments: u64,
+    overflow_randoms: u64,
+    overflow_waits: u64,
+    clock_regressions: u64,
+    max_clock_regression: Duration,
+    burst: u64,
+    max_burst: u64,
+    overflowed: bool,
+}
+
+impl GeneratorStats {
+    /// The number of Ulids produced
+    pub const fn generated(&self) -> u64 {
+        self.generated
+    }
+
+    /// The number of Ulids produced by incrementing the previous Ulid within the same millisecond
+    pub const fn increments(&self) -> u64 {
+        self.increments
+    }
+
+    /// The number of times the random bits of a millisecond were exhausted
+    pub const fn overflows(&self) -> u64 {
+        self.overflows
+    }
+
+    /// The number of overflows committed with [`crate::Overflow::commit_overflow_increment`]
+    pub const fn overflow_increments(&self) -> u64 {
+        self.overflow_increments
+    }
+
+    /// The number of overflows committed with [`crate::Overflow::commit_overflow_random`]
+    pub const fn overflow_randoms(&self) -> u64 {
+        self.overflow_randoms
+    }
+
+    /// The number of overflows which were resolved by the clock moving to the next millisecond
+    pub const fn overflow_waits(&self) -> u64 {
+        self.overflow_waits
+    }
+
+    /// The number of clock regressions detected. See [`crate::Generator::set_clock_regression`].
+    pub const fn clock_regressions(&self) -> u64 {
+        self.clock_regressions
+    }
+
+    /// The largest clock regression detected
+    pub const fn max_clock_regression(&self) -> Duration {
+        self.max_clock_regression
+    }
+
+    /// The largest number of Ulids produced within a single millisecond
+    pub const fn max_burst(&self) -> u64 {
+        self.max_burst
+    }
+
+    pub(crate) fn record_generated(&mut self, same_millisecond: bool) {
+        // an overflow which was not committed was resolved by the clock moving on
+        if self.overflowed {
+            self.overflowed = false;
+            self.overflow_waits += 1;
+        }
+        self.generated += 1;
+        self.burst = if same_millisecond { self.burst + 1 } else { 1 };
+        self.max_burst = self.max_burst.max(self.burst);
+    }
+
+    pub(crate) fn record_increment(&mut self) {
+        self.increments += 1;
+    }
+
+    /// Record an overflow, counting repeated attempts on the same overflowed Ulid once
+    pub(crate) fn record_overflow(&mut self) {
+        if !self.overflowed {
+            self.overflowed = true;
+            self.overflows += 1;
+        }
+    }
+
+    pub(crate) fn record_overflow_increment(&mut self) {
+        self.overflowed = false;
+        self.overflow_increments += 1;
+    }
+
+    pub(crate) fn record_overflow_random(&mut self) {
+        self.overflowed = false;
+        self.overflow_randoms += 1;
+    }
+
+    pub(crate) fn record_clock_regression(&mut self, duration: Duration, new: bool) {
+        if new {
+            self.clock_regressions += 1;
+        }
+        self.max_clock_regression = self.max_clock_regression.max(duration);
+    }
+
+    /// Export the statistics to the installed [`metrics`] recorder
+    ///
+    /// Counts are exported as counters named `ulid_generator_*_total`, the maximums as the gauges
+    /// `ulid_generator_max_clock_regression_seconds` and `ulid_generator_max_burst`. The given
+    /// labels are attached to every metric so that several generators can be told apart.
+    ///
+    /// # Example
+    /// ```rust
+    /// use ulid::Generator;
+    ///
+    /// let mut generator = Generator::new();
+    /// generator.enable_stats();
+    /// generator.generate().unwrap();
+    ///
+    /// let labels = [metrics::Label::new("generator", "orders")];
+    /// generator.stats().unwrap().record_metrics(&labels);
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn record_metrics(&self, labels: &[metrics::Label]) {
+        let counters = [
+            ("ulid_generator_generated_total", self.generated),
+            ("ulid_generator_increments_total", self.increments),
+            ("ulid_generator_overflows_total", self.overflows),
+            (
+                "ulid_generator_overflow_increments_total",
+                self.overflow_increments,
+            ),
+            (
+                "ulid_generator_overflow_randoms_total",
+                self.overflow_randoms,
+            ),
+            ("ulid_generator_overflow_waits_total", self.overflow_waits),
+            (
+                "ulid_generator_clock_regressions_total",
+                self.clock_regressions,
+            ),
+        ];
+        for (name, value) in counters {
+            metrics::counter!(name, labels.to_vec()).absolute(value);
+        }
+        metrics::gauge!(
+            "ulid_generator_max_clock_regression_seconds",
+            labels.to_vec()
+        )
+        .set(self.max_clock_regression);
+        metrics::gauge!("ulid_generator_max_burst", labels.to_vec()).set(self.max_burst as f64);
+    }
+}
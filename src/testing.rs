@@ -0,0 +1,187 @@
+//! Overrides of the clock and random number generator for tests.
+//!
+//! Functions such as [`Ulid::generate`](crate::Ulid::generate), [`Ulid::from_datetime`] and
+//! [`Generator::generate`](crate::Generator::generate) read the system clock and the thread-local
+//! random number generator. [`with_frozen`] replaces both on the current thread, so code which
+//! calls these functions internally produces predictable Ulids.
+//!
+//! [`Ulid::from_datetime`]: crate::Ulid::from_datetime
+
+use core::convert::Infallible;
+use std::cell::RefCell;
+use std::time::SystemTime;
+
+use rand::{rand_core::utils, TryRng};
+
+use crate::Clock;
+
+struct Frozen {
+    clock: Box<dyn Clock>,
+    rng: Box<dyn rand::Rng>,
+}
+
+thread_local! {
+    static FROZEN: RefCell<Option<Frozen>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with the clock and random number generator used by this crate replaced on the current
+/// thread
+///
+/// Every Ulid generated on this thread while `f` runs reads its time from `clock` and its random
+/// bits from `rng`, unless a clock or source is given explicitly. Overrides can be nested, and the
+/// previous state is restored when `f` returns or panics. Other threads are not affected.
+///
+/// # Example
+/// ```rust
+/// use std::time::{Duration, SystemTime};
+/// use ulid::{testing, StepRng, Ulid};
+///
+/// let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+///
+/// let ulid = testing::with_frozen(move || time, StepRng::new(0, 1), Ulid::generate);
+///
+/// assert_eq!(ulid.datetime(), time);
+/// assert_eq!(ulid.random(), 1);
+/// ```
+pub fn with_frozen<C, R, F, T>(clock: C, rng: R, f: F) -> T
+where
+    C: Clock + 'static,
+    R: rand::Rng + 'static,
+    F: FnOnce() -> T,
+{
+    struct Restore(Option<Frozen>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            FROZEN.with(|frozen| *frozen.borrow_mut() = previous);
+        }
+    }
+
+    let frozen = Frozen {
+        clock: Box::new(clock),
+        rng: Box::new(rng),
+    };
+    let _restore = Restore(FROZEN.with(|current| current.borrow_mut().replace(frozen)));
+    f()
+}
+
+/// The frozen time, if any
+///
+/// A clock which itself reads the system time sees the real time instead of recursing.
+pub(crate) fn now() -> Option<SystemTime> {
+    FROZEN.with(|frozen| {
+        let frozen = frozen.try_borrow_mut().ok()?;
+        frozen.as_ref().map(|frozen| frozen.clock.now())
+    })
+}
+
+/// The random number generator used when none is given, which reads from the frozen generator
+/// if any and from [`rand::rng`] otherwise
+#[derive(Debug, Clone)]
+pub(crate) struct DefaultRng(rand::rngs::ThreadRng);
+
+impl DefaultRng {
+    pub(crate) fn new() -> DefaultRng {
+        DefaultRng(rand::rng())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let frozen = FROZEN.with(|frozen| {
+            let mut frozen = frozen.try_borrow_mut().ok()?;
+            frozen.as_mut().map(|frozen| frozen.rng.next_u64())
+        });
+        frozen.unwrap_or_else(|| rand::Rng::next_u64(&mut self.0))
+    }
+}
+
+impl TryRng for DefaultRng {
+    type Error = Infallible;
+
+    #[inline]
+    fn try_next_u32(&mut self) -> Result<u32, Self::Error> {
+        Ok(self.next_u64() as u32)
+    }
+
+    #[inline]
+    fn try_next_u64(&mut self) -> Result<u64, Self::Error> {
+        Ok(self.next_u64())
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Self::Error> {
+        utils::fill_bytes_via_next_word(dest, || self.try_next_u64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Generator, StepRng, Ulid};
+    use std::time::Duration;
+
+    fn time() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+    }
+
+    #[test]
+    fn test_frozen_generate() {
+        let time = time();
+        let (ulid1, ulid2) = with_frozen(
+            move || time,
+            StepRng::new(7, 0),
+            || (Ulid::generate(), Ulid::generate()),
+        );
+        assert_eq!(ulid1, ulid2);
+        assert_eq!(ulid1.datetime(), time);
+        assert_eq!(ulid1.random(), 7 << 64 | 7);
+
+        assert_ne!(Ulid::generate().datetime(), time);
+    }
+
+    #[test]
+    fn test_frozen_generator() {
+        let time = time();
+        let ulids: Vec<_> = with_frozen(
+            move || time,
+            StepRng::new(0, 1),
+            || {
+                let mut generator = Generator::new();
+                generator.iter().take(3).collect()
+            },
+        );
+        assert_eq!(ulids[0].datetime(), time);
+        assert_eq!(ulids[0].random(), 1);
+        assert!(ulids.windows(2).all(|pair| pair[0].0 + 1 == pair[1].0));
+    }
+
+    #[test]
+    fn test_nested_and_restored() {
+        let outer = time();
+        let inner = outer + Duration::from_secs(1);
+        with_frozen(
+            move || outer,
+            StepRng::new(0, 0),
+            || {
+                let result = std::panic::catch_unwind(|| {
+                    with_frozen(
+                        move || inner,
+                        StepRng::new(0, 0),
+                        || {
+                            assert_eq!(Ulid::generate().datetime(), inner);
+                            panic!("restore on panic");
+                        },
+                    )
+                });
+                assert!(result.is_err());
+                assert_eq!(Ulid::generate().datetime(), outer);
+            },
+        );
+    }
+
+    #[test]
+    fn test_clock_reading_system_time() {
+        let ulid = with_frozen(crate::SystemClock, StepRng::new(0, 0), Ulid::generate);
+        assert!(ulid.datetime() > time());
+    }
+}
@@ -46,7 +46,7 @@ impl Ulid {
     /// let ulid = Ulid::from_datetime(SystemTime::now());
     /// ```
     pub fn from_datetime(datetime: SystemTime) -> Ulid {
-        Ulid::from_datetime_with_source(datetime, &mut rand::rng())
+        Ulid::from_datetime_with_source(datetime, &mut crate::time_utils::rng())
     }
 
     /// Creates a new Ulid with the given datetime and random number generator
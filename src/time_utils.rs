@@ -1,4 +1,13 @@
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) use std::time::Instant;
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) use web_time::Instant;
+
 pub(crate) fn now() -> std::time::SystemTime {
+    #[cfg(feature = "testing")]
+    if let Some(now) = crate::testing::now() {
+        return now;
+    }
     #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
     {
         use web_time::web::SystemTimeExt;
@@ -7,3 +16,15 @@ pub(crate) fn now() -> std::time::SystemTime {
     #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
     return std::time::SystemTime::now();
 }
+
+/// The random number generator used when none is given
+#[cfg(not(feature = "testing"))]
+pub(crate) fn rng() -> rand::rngs::ThreadRng {
+    rand::rng()
+}
+
+/// The random number generator used when none is given, which can be overridden in tests
+#[cfg(feature = "testing")]
+pub(crate) fn rng() -> crate::testing::DefaultRng {
+    crate::testing::DefaultRng::new()
+}
@@ -0,0 +1,103 @@
+use std::collections::HashSet;
+use std::env;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::thread;
+
+use ulid::{OverflowPolicy, SharedGenerator, Ulid};
+
+const CHILD_ENV: &str = "ULID_SHARED_GENERATOR_PATH";
+const PROCESSES: usize = 4;
+const PER_PROCESS: usize = 2000;
+
+/// Runs inside each spawned process, and does nothing when run directly
+#[test]
+fn shared_generator_child() {
+    let path = match env::var_os(CHILD_ENV) {
+        Some(path) => PathBuf::from(path),
+        None => return,
+    };
+    let mut generator = SharedGenerator::open(path).unwrap();
+    generator.set_policy(OverflowPolicy::Sleep);
+    let mut previous = Ulid::nil();
+    for _ in 0..PER_PROCESS {
+        let ulid = generator.generate().unwrap();
+        assert!(ulid > previous);
+        previous = ulid;
+        println!("ULID {}", ulid);
+    }
+}
+
+#[test]
+fn shared_generator_across_processes() {
+    let path = env::temp_dir().join(format!("ulid-shared-processes-{}", std::process::id()));
+    SharedGenerator::create(&path).unwrap();
+
+    let children: Vec<_> = (0..PROCESSES)
+        .map(|_| {
+            Command::new(env::current_exe().unwrap())
+                .args(["shared_generator_child", "--exact", "--nocapture"])
+                .env(CHILD_ENV, &path)
+                .stdout(Stdio::piped())
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+
+    let mut all = HashSet::new();
+    for child in children {
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let ulids: Vec<Ulid> = stdout
+            .lines()
+            // the test harness may print on the same line as the first Ulid
+            .filter_map(|line| line.split_once("ULID "))
+            .map(|(_, text)| text.parse().unwrap())
+            .collect();
+        assert_eq!(ulids.len(), PER_PROCESS);
+        all.extend(ulids);
+    }
+    assert_eq!(all.len(), PROCESSES * PER_PROCESS);
+
+    let last = SharedGenerator::open(&path).unwrap().last().unwrap();
+    assert_eq!(all.iter().max(), Some(&last));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn shared_generator_across_threads() {
+    const THREADS: usize = 8;
+    const PER_THREAD: usize = 20_000;
+
+    let path = env::temp_dir().join(format!("ulid-shared-threads-{}", std::process::id()));
+    let mut generator = SharedGenerator::create(&path).unwrap();
+    generator.set_policy(OverflowPolicy::BorrowFuture);
+    let generator = Arc::new(generator);
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let generator = Arc::clone(&generator);
+            thread::spawn(move || {
+                let mut previous = Ulid::nil();
+                (0..PER_THREAD)
+                    .map(|_| {
+                        let ulid = generator.generate().unwrap();
+                        assert!(ulid > previous);
+                        previous = ulid;
+                        ulid
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+
+    let mut all = HashSet::new();
+    for handle in handles {
+        all.extend(handle.join().unwrap());
+    }
+    assert_eq!(all.len(), THREADS * PER_THREAD);
+    assert_eq!(all.iter().max(), Some(&generator.last().unwrap()));
+    std::fs::remove_file(&path).unwrap();
+}